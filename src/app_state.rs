@@ -1,13 +1,6 @@
 use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone, Copy, PartialEq)]
-pub enum InputMode {
-    YiOnly,           // 彝文
-    PinyinYi,         // 拼音+彝文
-    PinyinWithYi,     // 拼音（彝文）
-    YiWithPinyin,     // 彝文（拼音）
-    HtmlRuby,         // HTML注音
-}
+pub use yi_core::InputMode;
 
 // 添加英文输入状态枚举
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -17,12 +10,6 @@ pub enum EnglishInputState {
     UpperCase,    // 英文大写输入模式
 }
 
-impl Default for InputMode {
-    fn default() -> Self {
-        InputMode::YiOnly
-    }
-}
-
 impl Default for EnglishInputState {
     fn default() -> Self {
         EnglishInputState::Yi
@@ -36,6 +23,8 @@ pub struct AppState {
     pub injecting_text: Arc<Mutex<bool>>,
     pub input_mode: Arc<Mutex<InputMode>>,
     pub english_input_state: Arc<Mutex<EnglishInputState>>, // 新增英文输入状态
+    pub toneless: Arc<Mutex<bool>>,    // 无声调输入
+    pub correction: Arc<Mutex<bool>>,  // 拼写纠错
 }
 
 impl AppState {
@@ -46,6 +35,8 @@ impl AppState {
             injecting_text: Arc::new(Mutex::new(false)),
             input_mode: Arc::new(Mutex::new(InputMode::default())),
             english_input_state: Arc::new(Mutex::new(EnglishInputState::default())),
+            toneless: Arc::new(Mutex::new(false)),
+            correction: Arc::new(Mutex::new(false)),
         }
     }
     
@@ -84,4 +75,24 @@ impl AppState {
             EnglishInputState::default()
         }
     }
-}
\ No newline at end of file
+    
+    pub fn set_toneless(&self, enabled: bool) {
+        if let Ok(mut state) = self.toneless.lock() {
+            *state = enabled;
+        }
+    }
+    
+    pub fn get_toneless(&self) -> bool {
+        self.toneless.lock().map(|state| *state).unwrap_or(false)
+    }
+    
+    pub fn set_correction(&self, enabled: bool) {
+        if let Ok(mut state) = self.correction.lock() {
+            *state = enabled;
+        }
+    }
+    
+    pub fn get_correction(&self) -> bool {
+        self.correction.lock().map(|state| *state).unwrap_or(false)
+    }
+}
\ No newline at end of file
@@ -11,6 +11,8 @@ use winapi::um::dwmapi::*;
 use winapi::shared::winerror::*;
 use winapi::um::winreg::*;
 use winapi::um::winnt::*;
+use yi_core::{Candidate, CandidateKind, CandidateSource};
+use crate::i18n::t;
 
 const DWMWA_USE_IMMERSIVE_DARK_MODE: u32 = 20;
 
@@ -19,7 +21,6 @@ static mut GLOBAL_CANDIDATES: Option<Arc<Mutex<Vec<String>>>> = None;
 pub struct CandidateWindow {
     hwnd: HWND,
     candidates: Arc<Mutex<Vec<String>>>,
-    selected_index: usize,
     current_input: Arc<Mutex<String>>,
     is_dark_mode: bool,
 }
@@ -88,17 +89,16 @@ impl CandidateWindow {
         let window = CandidateWindow {
             hwnd,
             candidates,
-            selected_index: 0,
             current_input,
             is_dark_mode,
         };
         Ok(window)
     }
     
-    pub fn show_candidates(&mut self, candidates: Vec<String>, input: &str) {
+    pub fn show_candidates(&mut self, candidates: &[Candidate], input: &str) {
                 
         if let Ok(mut guard) = self.candidates.lock() {
-            *guard = candidates;
+            *guard = candidates.iter().map(display_text).collect();
         }
         
         // 更新当前输入
@@ -106,8 +106,6 @@ impl CandidateWindow {
             *input_guard = input.to_string();
         }
         
-        self.selected_index = 0;
-        
         // 只要有输入内容就显示窗口（不管是否有候选词）
         if !input.is_empty() {
             unsafe {
@@ -140,38 +138,12 @@ impl CandidateWindow {
         }
     }
 
-    pub fn select_by_number(&mut self, number: usize) -> Option<String> {
-        if let Ok(candidates) = self.candidates.lock() {
-            if number > 0 && number <= candidates.len() {
-                return Some(candidates[number - 1].clone());
-            }
-        }
-        None
-    }
-    
-    pub fn get_selected_candidate(&self) -> Option<String> {
-        if let Ok(candidates) = self.candidates.lock() {
-            if self.selected_index < candidates.len() {
-                return Some(candidates[self.selected_index].clone());
-            }
-        }
-        None
-    }
-    
     pub fn hide(&self) {
         unsafe {
             ShowWindow(self.hwnd, SW_HIDE);
         }
     }
     
-    pub fn get_candidates_count(&self) -> usize {
-        if let Ok(candidates) = self.candidates.lock() {
-            candidates.len()
-        } else {
-            0
-        }
-    }
-    
     pub fn create_window(&mut self) -> Result<(), Box<dyn std::error::Error>> {
         unsafe {
             // 注册窗口类
@@ -214,6 +186,28 @@ impl CandidateWindow {
     }
 }
 
+// 候选项的显示文本，例如 "ꆈꌠ (nuo-su)"，部首带有本地化的前缀
+fn display_text(candidate: &Candidate) -> String {
+    match candidate.kind {
+        CandidateKind::Radical => format!(
+            "[{}] {} ({})",
+            t("radical_prefix"),
+            candidate.text,
+            candidate.pinyin()
+        ),
+        _ if candidate.source == CandidateSource::Correction => {
+            format!("≈ {} ({})", candidate.text, candidate.pinyin())
+        }
+        _ if candidate.gloss.is_some() => format!(
+            "{} ({}) {}",
+            candidate.text,
+            candidate.pinyin(),
+            candidate.gloss.as_deref().unwrap_or_default()
+        ),
+        _ => format!("{} ({})", candidate.text, candidate.pinyin()),
+    }
+}
+
 // 检测系统是否为深色模式
 fn detect_dark_mode() -> bool {
     unsafe {
@@ -536,4 +530,4 @@ unsafe extern "system" fn window_proc(
 
 fn to_wide_string(s: &str) -> Vec<u16> {
     OsStr::new(s).encode_wide().chain(std::iter::once(0)).collect()
-}
\ No newline at end of file
+}
\ No newline at end of file
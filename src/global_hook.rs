@@ -281,6 +281,25 @@ unsafe extern "system" fn keyboard_proc(
                 }
                 return 1; // 阻止按键传递给应用程序
             }
+            // 音节分隔符 ' 和 -（缓冲区不为空且未按 Shift 时）
+            else if !INPUT_BUFFER_EMPTY && !ctrl_pressed && !alt_pressed && !shift_pressed && (
+                kb_struct.vkCode == 0xDE || // ' 键
+                kb_struct.vkCode == 0xBD    // - 键
+            ) {
+                println!("发送音节分隔符事件: {}", kb_struct.vkCode);
+                if let Some(ref sender) = GLOBAL_SENDER {
+                    let event = KeyEvent {
+                        vk_code: kb_struct.vkCode,
+                        scan_code: kb_struct.scanCode,
+                        flags: kb_struct.flags,
+                        is_key_down,
+                    };
+                    if let Err(e) = sender.send(event) {
+                        println!("发送事件失败: {:?}", e);
+                    }
+                }
+                return 1; // 阻止按键传递给应用程序
+            }
             // 所有其他按键（包括组合键）都让系统处理
             else {
                 return CallNextHookEx(std::ptr::null_mut(), n_code, w_param, l_param);
@@ -313,4 +332,4 @@ pub fn get_english_input_state() -> EnglishInputState {
     unsafe {
         ENGLISH_INPUT_STATE
     }
-}
\ No newline at end of file
+}
\ No newline at end of file
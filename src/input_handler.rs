@@ -1,377 +1,208 @@
 use crate::global_hook::KeyEvent;
 use crate::candidate_window::CandidateWindow;
 use crate::text_injector::TextInjector;
-use yi::YiIME;
+use yi_core::{Action, Composer, CorrectionOptions, Key, Modifiers, UserHistory, YiIME};
 use winapi::um::winuser::*;
+use std::path::PathBuf;
 use std::sync::Arc;
-use crate::app_state::{AppState, InputMode};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use crate::app_state::AppState;
+
+/// 两次保存输入历史之间的最短间隔
+const HISTORY_SAVE_INTERVAL: Duration = Duration::from_secs(30);
 
 pub struct InputHandler {
-    input_buffer: String,
-    yi_engine: Arc<YiIME>,
+    composer: Composer,
     app_state: Arc<AppState>,
+    /// 输入历史中是否有尚未保存的选择
+    history_dirty: bool,
+    /// 上一次开始保存输入历史的时间
+    last_history_save: Instant,
+    /// 正在后台保存输入历史的线程
+    history_saver: Option<JoinHandle<()>>,
 }
 
 impl InputHandler {
     pub fn new(yi_engine: Arc<YiIME>, app_state: Arc<AppState>) -> Self {
+        let history = match UserHistory::open(user_data_path("history.json")) {
+            Ok(history) => history,
+            Err(e) => {
+                println!("读取输入历史失败，使用空历史: {}", e);
+                UserHistory::new()
+            }
+        };
         Self {
-            input_buffer: String::new(),
-            yi_engine,
+            composer: Composer::new(yi_engine).with_history(history),
             app_state,
+            history_dirty: false,
+            last_history_save: Instant::now(),
+            history_saver: None,
         }
     }
-    
+
     pub fn handle_key_event(
-        &mut self, 
+        &mut self,
         event: KeyEvent,
         candidate_window: &mut CandidateWindow,
         text_injector: &TextInjector
     ) -> Result<bool, Box<dyn std::error::Error>> {
-                
+
         if !event.is_key_down {
             return Ok(false);
         }
-        
+
         // 更新全局钩子的缓冲区状态
-        crate::global_hook::set_input_buffer_empty(self.input_buffer.is_empty());
-        
-        // 处理退格键
-        if event.vk_code == VK_BACK as u32 {
-            if !self.input_buffer.is_empty() {
-                self.input_buffer.pop();
-                                
-                // 更新缓冲区状态
-                crate::global_hook::set_input_buffer_empty(self.input_buffer.is_empty());
-                
-                if self.input_buffer.is_empty() {
-                    candidate_window.hide();
-                                    } else {
-                    // 立即更新输入框显示，无论是否有候选词
-                    candidate_window.show_candidates(vec![], &self.input_buffer);
-                    // 返回 true 让主循环更新候选词（基于最后一次合法音节序列）
-                    return Ok(true);
-                }
-            }
-            return Ok(false);
-        }
-        
-        // 处理数字键1-9选择候选词
-        if event.vk_code >= 0x31 && event.vk_code <= 0x39 {
-            if !self.input_buffer.is_empty() {
-                let number = (event.vk_code - 0x30) as usize;
-                                
-                let candidates_count = candidate_window.get_candidates_count();
-                
-                if number <= candidates_count {
-                    if let Some(selected) = candidate_window.select_by_number(number) {
-                                                self.commit_text(&selected, candidate_window, text_injector)?;
-                        return Ok(false);
-                    }
-                } else {
-                                    }
-            }
+        crate::global_hook::set_input_buffer_empty(self.composer.is_empty());
+
+        let Some(key) = translate_vk_code(event.vk_code) else {
             return Ok(false);
-        }
-        
-        // 处理字母键
-        if event.vk_code >= 0x41 && event.vk_code <= 0x5A {
-            let ch = (event.vk_code as u8 as char).to_lowercase().next().unwrap_or('\0');
-                        
-            if ch >= 'a' && ch <= 'z' {
-                // 直接添加字符到输入缓冲区
-                self.input_buffer.push(ch);
-                                
-                // 更新全局钩子的缓冲区状态
-                crate::global_hook::set_input_buffer_empty(false);
-                
-                // 检查输入序列是否合法
-                if self.is_valid_input_sequence(&self.input_buffer) {
-                    // 合法时需要更新候选项
-                    return Ok(true);
-                } else {
-                    // 不合法时显示输入框但不显示候选词
-                    candidate_window.show_candidates(vec![], &self.input_buffer);
-                }
+        };
+
+        // 检测修饰键状态
+        let modifiers = unsafe {
+            Modifiers {
+                shift: GetAsyncKeyState(VK_SHIFT) & 0x8000u16 as i16 != 0,
+                ctrl: GetAsyncKeyState(VK_CONTROL) & 0x8000u16 as i16 != 0,
+                alt: GetAsyncKeyState(VK_MENU) & 0x8000u16 as i16 != 0,
             }
-            return Ok(false);
-        }
-        
-        // 处理空格键
-        if event.vk_code == VK_SPACE as u32 {
-            if !self.input_buffer.is_empty() {
-                if let Some(selected) = candidate_window.get_selected_candidate() {
-                                        self.commit_text(&selected, candidate_window, text_injector)?;
+        };
+
+        // 根据托盘菜单中选择的输出形式提交文本
+        self.composer.set_input_mode(self.app_state.get_input_mode());
+
+        // 根据托盘菜单中的开关设置无声调输入和拼写纠错
+        let mut options = self.composer.options().clone();
+        options.toneless = self.app_state.get_toneless();
+        let correction = self.app_state.get_correction();
+        if options.correction.is_some() != correction {
+            options.correction = correction.then(CorrectionOptions::default);
+        }
+        self.composer.set_options(options);
+
+        let actions = self.composer.handle_key(key, modifiers);
+        let handled = !actions.is_empty();
+        let mut needs_refresh = false;
+
+        for action in actions {
+            match action {
+                Action::UpdatePreedit(_) | Action::UpdateCandidates(_) => needs_refresh = true,
+                Action::Commit(text) => {
+                    self.commit_text(&text, text_injector)?;
+                    // 输入历史由主循环定期保存，不在处理按键时写文件
+                    self.history_dirty = true;
                 }
+                Action::Hide => candidate_window.hide(),
             }
-            return Ok(false);
         }
-        
-        // 处理ESC键
-        if event.vk_code == VK_ESCAPE as u32 {
-                        self.input_buffer.clear();
-            crate::global_hook::set_input_buffer_empty(true);
-            candidate_window.hide();
-            return Ok(false);
-        }
-        
-        // 处理特殊标点符号按键（只有在缓冲区不为空时）
-        if !self.input_buffer.is_empty() {
-            // 检测Shift键状态
-            let shift_pressed = unsafe { GetAsyncKeyState(VK_SHIFT) & 0x8000u16 as i16 != 0 };
-            
-            let (punctuation_char, should_commit) = match event.vk_code {
-                0xDB => { // [ 键
-                    if shift_pressed {
-                        ("{", true) // Shift + [ = {
-                    } else {
-                        ("【", true) // [ = 【
-                    }
-                },
-                0xDD => { // ] 键
-                    if shift_pressed {
-                        ("}", true) // Shift + ] = }
-                    } else {
-                        ("】", true) // ] = 】
-                    }
-                },
-                0xDC => { // \ 键
-                    if shift_pressed {
-                        ("|", true) // Shift + \ = |
-                    } else {
-                        ("、", true) // \ = 、
-                    }
-                },
-                0xBA => { // ; 键
-                    if shift_pressed {
-                        ("：", true) // Shift + ; = :
-                    } else {
-                        ("；", true) // ; = ；
-                    }
-                },
-                0xBC => { // , 键
-                    if shift_pressed {
-                        ("《", true) // Shift + , = <
-                    } else {
-                        ("，", true) // , = ，
-                    }
-                },
-                0xBE => { // . 键
-                    if shift_pressed {
-                        ("》", true) // Shift + . = >
-                    } else {
-                        ("。", true) // . = 。
-                    }
-                },
-                _ => ("", false)
-            };
-            
-            if should_commit {
-                // 先提交第一个候选词
-                if let Some(selected) = candidate_window.get_selected_candidate() {
-                    // 提取彝文文本和拼音
-                    let (yi_text, pinyin) = self.extract_yi_and_pinyin(&selected);
-                    
-                    // 根据输入模式格式化文本
-                    let formatted_text = self.format_text_by_mode(&yi_text, &pinyin);
-                    
-                    // 组合文本：格式化后的文本 + 标点
-                    let combined_text = format!("{}{}", formatted_text, punctuation_char);
-                    
-                    // 设置正在注入文本的标志
-                    crate::global_hook::set_injecting_text(true);
-                    
-                    // 注入组合文本
-                    text_injector.inject_text(&combined_text)?;
-                    
-                    // 等待文本注入完成
-                    std::thread::sleep(std::time::Duration::from_millis(10));
-                    
-                    // 重置注入标志
-                    crate::global_hook::set_injecting_text(false);
-                    
-                    // 清空输入缓冲区并隐藏候选窗口
-                    self.input_buffer.clear();
-                    crate::global_hook::set_input_buffer_empty(true);
-                    candidate_window.hide();
-                }
-                return Ok(false);
-            }
+
+        // 更新缓冲区状态
+        crate::global_hook::set_input_buffer_empty(self.composer.is_empty());
+
+        if needs_refresh {
+            // 即使没有候选词，也要显示输入框
+            candidate_window.show_candidates(
+                self.composer.candidates(),
+                self.composer.preedit()
+            );
         }
-        
-        Ok(false)
+
+        Ok(handled)
     }
-    
+
     pub fn get_input_buffer(&self) -> &str {
-        &self.input_buffer
+        self.composer.preedit()
     }
-    
-    pub fn clear_input_buffer(&mut self) {
-        self.input_buffer.clear();
-        crate::global_hook::set_input_buffer_empty(true);
-    }
-    
-    fn format_text_by_mode(&self, yi_text: &str, pinyin: &str) -> String {
-        let mode = self.app_state.get_input_mode();
-        
-        match mode {
-            InputMode::YiOnly => yi_text.to_string(),
-            InputMode::PinyinYi => {
-                // 拼音+彝文：先输入拼音（音节间用空格代替短横线），跟随一个空格，再跟随彝文
-                let formatted_pinyin = pinyin.replace("-", " ");
-                format!("{} {}", formatted_pinyin, yi_text)
-            },
-            InputMode::PinyinWithYi => {
-                // 拼音（彝文）：先输入拼音，小括号内有彝文
-                let formatted_pinyin = pinyin.replace("-", " ");
-                format!("{}（{}）", formatted_pinyin, yi_text)
-
-            },
-            InputMode::YiWithPinyin => {
-                // 彝文（拼音）：先输入彝文，小括号内有拼音
-                let formatted_pinyin = pinyin.replace("-", " ");
-                format!("{}（{}）", yi_text, formatted_pinyin)
 
-            },
-            InputMode::HtmlRuby => {
-                // HTML注音：每个彝文字符都用ruby标签包装
-                self.format_as_html_ruby(yi_text, pinyin)
-            },
+    /// 有未保存的选择且距上次保存超过 HISTORY_SAVE_INTERVAL 时，在后台线程保存输入历史
+    pub fn save_history_if_due(&mut self) {
+        if !self.history_dirty || self.last_history_save.elapsed() < HISTORY_SAVE_INTERVAL {
+            return;
         }
+        // 上一次保存还没有完成时留到下次
+        if self.history_saver.as_ref().is_some_and(|saver| !saver.is_finished()) {
+            return;
+        }
+
+        let history = self.composer.history().clone();
+        self.history_saver = Some(std::thread::spawn(move || {
+            if let Err(e) = history.save() {
+                println!("保存输入历史失败: {}", e);
+            }
+        }));
+        self.history_dirty = false;
+        self.last_history_save = Instant::now();
     }
-    
-    fn format_as_html_ruby(&self, yi_text: &str, pinyin: &str) -> String {
-        let yi_chars: Vec<char> = yi_text.chars().collect();
-        let pinyin_parts: Vec<&str> = pinyin.split('-').collect();
-        
-        let mut result = String::new();
-        
-        for (i, yi_char) in yi_chars.iter().enumerate() {
-            let corresponding_pinyin = pinyin_parts.get(i).unwrap_or(&"");
-            result.push_str(&format!(
-                "<ruby>{}<rp>(</rp><rt>{}</rt><rp>)</rp></ruby>",
-                yi_char, corresponding_pinyin
-            ));
+
+    /// 等待后台保存完成，再保存剩下的选择（退出时调用）
+    pub fn flush_history(&mut self) {
+        if let Some(saver) = self.history_saver.take() {
+            let _ = saver.join();
+        }
+        if self.history_dirty {
+            if let Err(e) = self.composer.history().save() {
+                println!("保存输入历史失败: {}", e);
+            }
+            self.history_dirty = false;
         }
-        
-        result
     }
-    
+
     fn commit_text(
-        &mut self, 
+        &self,
         text: &str,
-        candidate_window: &mut CandidateWindow,
         text_injector: &TextInjector
     ) -> Result<(), Box<dyn std::error::Error>> {
-                
-        // 提取彝文文本和拼音
-        let (yi_text, pinyin) = self.extract_yi_and_pinyin(text);
-        
-        // 根据输入模式格式化文本
-        let formatted_text = self.format_text_by_mode(&yi_text, &pinyin);
-        
         // 设置正在注入文本的标志，避免拦截 ourselves发送的按键
         crate::global_hook::set_injecting_text(true);
-        
-        // 使用格式化后的文本进行注入
-        text_injector.inject_text(&formatted_text)?;
-        
+
+        // 注入格式化后的文本
+        let result = text_injector.inject_text(text);
+
         // 等待一小段时间确保文本注入完成
         std::thread::sleep(std::time::Duration::from_millis(10));
-        
+
         // 重置注入标志
         crate::global_hook::set_injecting_text(false);
-        
-        // 清空输入缓冲区并隐藏候选窗口
-        self.input_buffer.clear();
-        
-        // 更新全局钩子的缓冲区状态
-        crate::global_hook::set_input_buffer_empty(true);
-        
-        candidate_window.hide();
-        
-        Ok(())
-    }
-    
-    fn extract_yi_and_pinyin(&self, text: &str) -> (String, String) {
-        // 解析候选项文本，提取彝文和拼音
-        if let Some(start) = text.find(" (") {
-            if let Some(end) = text.rfind(')') {
-                let yi_part = text[..start].trim();
-                let pinyin_part = text[start + 2..end].trim();
-                
-                // 处理部首标记
-                let clean_yi = if yi_part.starts_with("[部首] ") {
-                    &yi_part[9..]
-                } else {
-                    yi_part
-                };
-                
-                return (clean_yi.to_string(), pinyin_part.to_string());
-            }
-        }
-        
-        // 如果解析失败，返回原文本作为彝文，空字符串作为拼音
-        (text.to_string(), String::new())
-    }
 
-    fn is_valid_input_sequence(&self, input: &str) -> bool {
-        // 1. 检查是否为完整音节
-        if self.yi_engine.syllable_set.contains(input) {
-            return true;
-        }
-        
-        // 2. 检查是否为潜在的声母或声母组合
-        if input.len() <= 3 && self.is_potential_consonant(input) {
-            return true;
-        }
-        
-        // 3. 检查是否能通过智能分词形成有效组合
-        let segment_results = self.yi_engine.segment_pinyin(input);
-        if !segment_results.is_empty() {
-            return true;
-        }
-        
-        // 4. 检查是否为部分有效音节（允许用户继续输入）
-        // 例如：用户输入"zh"，虽然不是完整音节，但可能要输入"zha"、"zhe"等
-        for syllable in &self.yi_engine.syllable_set {
-            if syllable.starts_with(input) {
-                return true;
-            }
-        }
-        
-        // 5. 检查部首拼音的前缀匹配
-        for pinyin in self.yi_engine.radical_pinyin_index.keys() {
-            if pinyin.starts_with(input) {
-                return true;
-            }
-        }
-        
-        false
+        result
     }
+}
 
-    fn is_potential_consonant(&self, input: &str) -> bool {
-        // 检查是否有以此开头的音节
-        self.yi_engine.pinyin_index.keys().any(|pinyin| pinyin.starts_with(input)) ||
-        self.yi_engine.radical_pinyin_index.keys().any(|pinyin| pinyin.starts_with(input))
-    }
-    
-    fn extract_yi_text<'a>(&self, text: &'a str) -> &'a str {
-        // 提取实际的彝文文本（去掉括号中的拼音部分和[部首]标记）
-        if let Some(pos) = text.find(" (") {
-            let base_text = &text[..pos];
-            // 如果包含[部首]标记，去除它
-            if base_text.starts_with("[部首] ") {
-                &base_text[9..] // 去掉"[部首] "前缀（9个字节，因为包含中文字符）
-            } else {
-                base_text
-            }
-        } else {
-            // 处理没有括号的情况，也可能包含[部首]标记
-            if text.starts_with("[部首] ") {
-                &text[9..]
-            } else {
-                text
-            }
-        }
+impl Drop for InputHandler {
+    fn drop(&mut self) {
+        self.flush_history();
     }
-}
\ No newline at end of file
+}
+
+/// 将 Windows 虚拟键码翻译为与平台无关的按键
+fn translate_vk_code(vk_code: u32) -> Option<Key> {
+    let key = match vk_code {
+        code if code == VK_BACK as u32 => Key::Backspace,
+        code if code == VK_SPACE as u32 => Key::Space,
+        code if code == VK_ESCAPE as u32 => Key::Escape,
+        // 数字键1-9选择候选词
+        0x31..=0x39 => Key::Select((vk_code - 0x30) as usize),
+        // 字母键
+        0x41..=0x5A => Key::Char((vk_code as u8 as char).to_ascii_lowercase()),
+        // 特殊标点符号按键
+        0xDB => Key::Char('['),
+        0xDD => Key::Char(']'),
+        0xDC => Key::Char('\\'),
+        0xBA => Key::Char(';'),
+        0xBC => Key::Char(','),
+        0xBE => Key::Char('.'),
+        // 音节分隔符
+        0xDE => Key::Char('\''),
+        0xBD => Key::Char('-'),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// 用户数据文件（输入历史、用户词库等）：%APPDATA%\Yi\<file_name>
+pub fn user_data_path(file_name: &str) -> PathBuf {
+    let base = std::env::var_os("APPDATA")
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."));
+    base.join("Yi").join(file_name)
+}
@@ -1,6 +0,0 @@
-pub mod ime;
-pub mod segmentation;
-pub mod conversion;
-
-pub use ime::YiIME;
-pub use segmentation::SegmentResult;
\ No newline at end of file
@@ -1,51 +1,102 @@
-#![windows_subsystem = "windows"]
+#![cfg_attr(windows, windows_subsystem = "windows")]
 
+#[cfg(windows)]
 mod global_hook;
+#[cfg(windows)]
 mod candidate_window;
+#[cfg(windows)]
 mod text_injector;
+#[cfg(windows)]
 mod tray_icon;
+#[cfg(windows)]
 mod input_handler;
-mod candidate_manager;
+#[cfg(windows)]
 mod app_state;
+#[cfg(windows)]
 mod tsf_bridge;
+#[cfg(windows)]
 mod i18n;
 
+#[cfg(windows)]
 use crate::global_hook::{GlobalHook, KeyEvent};
+#[cfg(windows)]
 use crate::candidate_window::CandidateWindow;
+#[cfg(windows)]
 use crate::text_injector::TextInjector;
+#[cfg(windows)]
 use crate::tray_icon::TrayIcon;
-use crate::input_handler::InputHandler;
-use crate::candidate_manager::CandidateManager;
+#[cfg(windows)]
+use crate::input_handler::{user_data_path, InputHandler};
+#[cfg(windows)]
 use crate::app_state::AppState;
-use yi::YiIME;
+#[cfg(windows)]
+use yi_core::{BigramModel, LoadMode, YiIME, YI_LEXICON, YI_RADICAL_DICT, YI_SYLLABLE_DICT};
+#[cfg(windows)]
 use winapi::um::winuser::*;
+#[cfg(windows)]
 use std::sync::mpsc::Receiver;
+#[cfg(windows)]
 use std::thread;
+#[cfg(windows)]
 use std::time::Duration;
+#[cfg(windows)]
 use winapi::shared::windef::*;
 
+#[cfg(windows)]
 struct GlobalIME {
     hook: GlobalHook,
     candidate_window: CandidateWindow,
     tray_icon: TrayIcon,
     input_handler: InputHandler,
-    candidate_manager: CandidateManager,
     text_injector: TextInjector,  // 直接持有 TextInjector
     app_state: AppState,
     key_receiver: Receiver<KeyEvent>,
 }
 
-// 在文件顶部添加嵌入的字典数据
-const YI_SYLLABLE_DICT: &str = include_str!("../assets/彝文音节字典.json");
-const YI_RADICAL_DICT: &str = include_str!("../assets/彝文部首字典.json");
-
+#[cfg(windows)]
 impl GlobalIME {
     fn new() -> Result<Self, Box<dyn std::error::Error>> {
+        // 优先使用 yi-train 生成的带频率的音节字典 %APPDATA%\Yi\frequency.json，
+        // 不存在或加载失败时使用嵌入的字典数据
         let mut yi_engine = YiIME::new();
-        
-        // 使用嵌入的字典数据
-        yi_engine.load_dictionary_from_str(YI_SYLLABLE_DICT)?;
+        let dict_path = user_data_path("frequency.json");
+        let loaded = dict_path.exists()
+            && match yi_engine.load_dictionary(&dict_path.to_string_lossy()) {
+                Ok(()) => true,
+                Err(e) => {
+                    println!("读取频率字典失败，使用内置字典: {}", e);
+                    false
+                }
+            };
+        if !loaded {
+            yi_engine.load_dictionary_from_str(YI_SYLLABLE_DICT)?;
+        }
         yi_engine.load_radical_dictionary_from_str(YI_RADICAL_DICT)?;
+        // 频率字典缺少某些读音时跳过相应的词，而不是无法启动
+        for warning in yi_engine.load_lexicon_with_mode(YI_LEXICON, LoadMode::Lenient)?.warnings {
+            println!("词库: {}", warning);
+        }
+
+        // 加载用户词库 %APPDATA%\Yi\phrases.tsv（如果存在），有问题的行跳过并提示
+        if let Ok(content) = std::fs::read_to_string(user_data_path("phrases.tsv")) {
+            match yi_engine.user_dictionary.import_tsv(&content, LoadMode::Lenient) {
+                Ok(report) => {
+                    for warning in &report.warnings {
+                        println!("用户词库: {}", warning);
+                    }
+                }
+                Err(e) => println!("读取用户词库失败: {}", e),
+            }
+        }
+
+        // 加载语言模型 %APPDATA%\Yi\bigram.txt（如果存在），否则按音节长度估计分词的置信度
+        let model_path = user_data_path("bigram.txt");
+        if model_path.exists() {
+            match BigramModel::load(&model_path.to_string_lossy()) {
+                Ok(model) => yi_engine.set_language_model(model),
+                Err(e) => println!("读取语言模型失败: {}", e),
+            }
+        }
         
         let (mut hook, key_receiver) = GlobalHook::new();
         hook.install()?;
@@ -65,8 +116,16 @@ impl GlobalIME {
             }
         });
         
-        let input_handler = InputHandler::new(yi_engine.clone().into(), app_state.clone().into());
-        let candidate_manager = CandidateManager::new(yi_engine.into());
+        // 设置无声调输入和拼写纠错开关的回调
+        tray_icon.set_engine_options_callback({
+            let app_state_clone = app_state.clone();
+            move |toneless, correction| {
+                app_state_clone.set_toneless(toneless);
+                app_state_clone.set_correction(correction);
+            }
+        });
+        
+        let input_handler = InputHandler::new(yi_engine.into(), app_state.clone().into());
         
         // 初始化英文输入状态
         app_state.set_english_input_state(crate::app_state::EnglishInputState::Yi);
@@ -76,7 +135,6 @@ impl GlobalIME {
             candidate_window,
             tray_icon,
             input_handler,
-            candidate_manager,
             text_injector,  // 直接使用 TextInjector
             app_state,
             key_receiver,
@@ -102,6 +160,9 @@ impl GlobalIME {
                 while let Ok(key_event) = self.key_receiver.try_recv() {
                     self.handle_key_event(key_event)?;
                 }
+
+                // 定期在后台保存输入历史
+                self.input_handler.save_history_if_due();
                 
                 // 处理Windows消息
                 if PeekMessageW(&mut msg, std::ptr::null_mut(), 0, 0, PM_REMOVE) != 0 {
@@ -115,12 +176,15 @@ impl GlobalIME {
                 thread::sleep(Duration::from_millis(10));
             }
         }
+
+        // 退出前保存尚未写入的输入历史
+        self.input_handler.flush_history();
         
         Ok(())
     }
     
     fn handle_key_event(&mut self, event: KeyEvent) -> Result<(), Box<dyn std::error::Error>> {
-        // 委托给输入处理器
+        // 委托给输入处理器（候选词由组合器在处理按键时一并更新）
         self.input_handler.handle_key_event(
             event, 
             &mut self.candidate_window, 
@@ -132,16 +196,11 @@ impl GlobalIME {
             self.input_handler.get_input_buffer().is_empty()
         );
         
-        // 更新候选词
-        self.candidate_manager.update_candidates(
-            self.input_handler.get_input_buffer(),
-            &mut self.candidate_window
-        );
-        
         Ok(())
     }
 }
 
+#[cfg(windows)]
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 初始化国际化
     i18n::init_i18n()?;
@@ -151,3 +210,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut global_ime = GlobalIME::new()?;
     global_ime.run()
 }
+
+#[cfg(not(windows))]
+fn main() {
+    eprintln!("yi-global 仅支持 Windows，请使用 yi-core 库构建其他平台的前端");
+}
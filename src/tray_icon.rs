@@ -32,8 +32,16 @@ const ID_MENU_LANG_RU: i32 = 2014;
 const ID_MENU_LANG_JA: i32 = 2015;
 const ID_MENU_LANG_KO: i32 = 2016;     // 新增韩语
 
+// 输入选项菜单常量
+const ID_MENU_TONELESS: i32 = 2017;
+const ID_MENU_CORRECTION: i32 = 2018;
+
 static mut CURRENT_INPUT_MODE: InputMode = InputMode::YiOnly;
 static mut INPUT_MODE_CALLBACK: Option<Box<dyn Fn(InputMode) + Send + Sync>> = None;
+static mut CURRENT_TONELESS: bool = false;
+static mut CURRENT_CORRECTION: bool = false;
+// 参数为 (无声调输入, 拼写纠错)
+static mut ENGINE_OPTIONS_CALLBACK: Option<Box<dyn Fn(bool, bool) + Send + Sync>> = None;
 
 pub struct TrayIcon {
     hwnd: HWND,
@@ -140,6 +148,15 @@ impl TrayIcon {
         }
     }
     
+    pub fn set_engine_options_callback<F>(&self, callback: F)
+    where
+        F: Fn(bool, bool) + Send + Sync + 'static,
+    {
+        unsafe {
+            ENGINE_OPTIONS_CALLBACK = Some(Box::new(callback));
+        }
+    }
+    
 }
 
 // 修改create_context_menu函数
@@ -176,6 +193,20 @@ unsafe fn create_context_menu() -> HMENU {
         to_wide_string(&t("menu_input_mode")).as_ptr()
     );
     
+    // 添加"无声调输入"和"拼写纠错"开关
+    AppendMenuW(
+        hmenu,
+        MF_STRING | if CURRENT_TONELESS { MF_CHECKED } else { 0 },
+        ID_MENU_TONELESS as usize,
+        to_wide_string(&t("menu_toneless")).as_ptr()
+    );
+    AppendMenuW(
+        hmenu,
+        MF_STRING | if CURRENT_CORRECTION { MF_CHECKED } else { 0 },
+        ID_MENU_CORRECTION as usize,
+        to_wide_string(&t("menu_correction")).as_ptr()
+    );
+    
     // 添加语言选择子菜单
     let language_submenu = CreatePopupMenu();
     let languages = [
@@ -268,6 +299,16 @@ unsafe fn handle_menu_command(hwnd: HWND, cmd: i32) {
                 callback(InputMode::HtmlRuby);
             }
         }
+        ID_MENU_TONELESS | ID_MENU_CORRECTION => {
+            if cmd == ID_MENU_TONELESS {
+                CURRENT_TONELESS = !CURRENT_TONELESS;
+            } else {
+                CURRENT_CORRECTION = !CURRENT_CORRECTION;
+            }
+            if let Some(ref callback) = ENGINE_OPTIONS_CALLBACK {
+                callback(CURRENT_TONELESS, CURRENT_CORRECTION);
+            }
+        }
         ID_MENU_LANG_ZH => set_language(Language::ChineseSimplified),
 ID_MENU_LANG_ZH_TW => set_language(Language::ChineseTraditional),
 ID_MENU_LANG_EN => set_language(Language::English),
@@ -284,9 +325,8 @@ ID_MENU_LANG_KO => set_language(Language::Korean),
             nid.uID = ID_TRAY_ICON;
             Shell_NotifyIconW(NIM_DELETE, &mut nid);
             
-            // 强制退出进程，确保彻底关闭
-            use winapi::um::processthreadsapi::ExitProcess;
-            ExitProcess(0);
+            // 退出主消息循环：主循环保存输入历史后结束进程
+            PostQuitMessage(0);
         }
         _ => {}
     }
@@ -0,0 +1,265 @@
+use crate::ime::YiIME;
+use crate::session::Session;
+
+/// 候选窗口中最多显示的候选项数量（对应数字键 1-9）
+pub const MAX_CANDIDATES: usize = 9;
+
+/// 联想候选项的默认分数
+const COMPLETION_SCORE: f32 = 0.5;
+
+/// 候选项类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateKind {
+    /// 与输入完全匹配的单个音节
+    Syllable,
+    /// 彝文部首
+    Radical,
+    /// 由多个音节组成的词语
+    Phrase,
+    /// 根据声母或音节前缀联想出的音节
+    Completion,
+}
+
+/// 候选项来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CandidateSource {
+    /// 音节字典直接查询
+    Dictionary,
+    /// 部首字典
+    RadicalDictionary,
+    /// 智能分词转换
+    Segmentation,
+    /// 拼写纠错后的分词转换
+    Correction,
+    /// 用户输入历史
+    History,
+    /// 用户词库
+    UserDictionary,
+    /// 多音节词库：整个输入为词库中的一个词
+    Lexicon,
+}
+
+/// 候选项
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// 彝文文本
+    pub text: String,
+    /// 与彝文逐字对应的拼音音节
+    pub pinyin_segments: Vec<String>,
+    pub kind: CandidateKind,
+    /// 排序分数，越大越靠前
+    pub score: f32,
+    pub source: CandidateSource,
+    /// 释义（用户词语等带有释义的候选项）
+    pub gloss: Option<String>,
+}
+
+impl Candidate {
+    pub fn new(
+        text: impl Into<String>,
+        pinyin_segments: Vec<String>,
+        kind: CandidateKind,
+        score: f32,
+        source: CandidateSource,
+    ) -> Self {
+        Self {
+            text: text.into(),
+            pinyin_segments,
+            kind,
+            score,
+            source,
+            gloss: None,
+        }
+    }
+
+    /// 附加释义
+    pub fn with_gloss(mut self, gloss: Option<String>) -> Self {
+        self.gloss = gloss;
+        self
+    }
+
+    /// 用短横线连接的拼音，例如 "nuo-su"
+    pub fn pinyin(&self) -> String {
+        self.pinyin_segments.join("-")
+    }
+}
+
+impl YiIME {
+    /// 根据输入缓冲区生成候选项列表
+    pub fn candidates(&self, input: &str) -> Vec<Candidate> {
+        self.session_candidates(&Session::with_input(self, input))
+    }
+
+    /// 根据输入会话生成候选项列表，合法性检查和智能转换共用会话中的分词结果
+    pub fn session_candidates(&self, session: &Session) -> Vec<Candidate> {
+        let input = session.input();
+        if input.is_empty() || !self.is_valid_session(session) {
+            return Vec::new();
+        }
+
+        // 0. 用户词库中与输入完整匹配的词语最优先
+        let mut candidates = self.user_phrase_candidates(input);
+
+        // 1. 检查是否为完整音节
+        let is_complete_syllable = self.is_complete_syllable(input);
+
+        if is_complete_syllable {
+            // 添加完整音节的直接匹配结果（无声调输入时包括各声调的字），其余位置留给联想结果
+            let remaining = MAX_CANDIDATES.saturating_sub(candidates.len());
+            candidates.extend(self.query_by_pinyin(input).into_iter().take(remaining));
+        }
+
+        // 2. 检查是否应该进行声母联想（包括完整音节的联想）
+        if (input.len() <= 3 && self.is_potential_consonant(input)) || is_complete_syllable {
+            // 收集声母联想结果
+            let consonant_results = self.get_sorted_consonant_results(input);
+
+            // 如果是完整音节，跳过与输入完全相同的结果，只添加联想结果
+            for candidate in consonant_results {
+                if candidates.len() >= MAX_CANDIDATES {
+                    break;
+                }
+
+                // 如果是完整音节，跳过与输入相同的拼音
+                if is_complete_syllable && candidate.pinyin() == input {
+                    continue;
+                }
+
+                // 无声调输入时其他声调的字已经在直接匹配结果中
+                if candidates.iter().any(|c: &Candidate| c.text == candidate.text && c.pinyin_segments == candidate.pinyin_segments) {
+                    continue;
+                }
+
+                candidates.push(candidate);
+            }
+        }
+
+        // 3. 如果还没有足够的候选项，进行智能转换
+        if candidates.len() < MAX_CANDIDATES && !is_complete_syllable {
+            let remaining = MAX_CANDIDATES - candidates.len();
+            let conversions: Vec<Candidate> = self
+                .session_conversions(session)
+                .filter(|c| !candidates.iter().any(|e| e.text == c.text && e.pinyin_segments == c.pinyin_segments))
+                .take(remaining)
+                .collect();
+            candidates.extend(conversions);
+        }
+
+        candidates
+    }
+
+    /// 检查输入序列是否合法（可以继续输入或形成候选）
+    pub fn is_valid_input_sequence(&self, input: &str) -> bool {
+        self.is_valid_session(&Session::with_input(self, input))
+    }
+
+    /// 检查输入会话是否合法，使用会话中已经计算好的分词结果
+    pub fn is_valid_session(&self, session: &Session) -> bool {
+        let input = session.input();
+        // 1. 检查是否为完整音节
+        if self.is_complete_syllable(input) {
+            return true;
+        }
+
+        // 2. 检查是否为潜在的声母或声母组合
+        if input.len() <= 3 && self.is_potential_consonant(input) {
+            return true;
+        }
+
+        // 3. 检查是否能通过智能分词形成有效组合
+        if !session.segments().is_empty() {
+            return true;
+        }
+
+        // 4. 检查是否为部分有效音节或部首拼音（允许用户继续输入）
+        // 例如：用户输入"zh"，虽然不是完整音节，但可能要输入"zha"、"zhe"等
+        self.is_pinyin_prefix(input)
+    }
+
+    /// 检查是否有以此开头的音节或部首拼音
+    fn is_potential_consonant(&self, input: &str) -> bool {
+        self.is_pinyin_prefix(input)
+    }
+
+    /// 获取排序后的声母联想结果
+    fn get_sorted_consonant_results(&self, input: &str) -> Vec<Candidate> {
+        let mut consonant_results = Vec::new();
+        let mut priority_results = Vec::new(); // 优先结果：声母本身的候选项
+        let mut other_results = Vec::new();    // 其他结果
+
+        // 收集所有匹配的拼音和彝文字符
+        for pinyin in self.syllable_trie.completions(input) {
+            let Some(yi_chars) = self.pinyin_index.get(&pinyin) else {
+                continue;
+            };
+            for yi_char in yi_chars {
+                let result = Candidate::new(
+                    yi_char.clone(),
+                    vec![pinyin.clone()],
+                    CandidateKind::Completion,
+                    COMPLETION_SCORE * self.char_weight(yi_char),
+                    CandidateSource::Dictionary,
+                );
+
+                // 判断是否为声母本身的候选项
+                if Self::is_consonant_itself_candidate(input, &pinyin) {
+                    priority_results.push(result);
+                } else {
+                    other_results.push(result);
+                }
+            }
+        }
+
+        // 添加部首候选：部首不按频率加权，分数相同时按字符排序即为区块顺序
+        for pinyin in self.radical_trie.completions(input) {
+            for radical in self.get_radical_candidates(&pinyin) {
+                let result = Candidate::new(
+                    radical.clone(),
+                    vec![pinyin.clone()],
+                    CandidateKind::Radical,
+                    COMPLETION_SCORE,
+                    CandidateSource::RadicalDictionary,
+                );
+
+                if Self::is_consonant_itself_candidate(input, &pinyin) {
+                    priority_results.push(result);
+                } else {
+                    other_results.push(result);
+                }
+            }
+        }
+
+        // 按使用频率排序，频率相同时按字符和拼音排序，然后去重
+        for results in [&mut priority_results, &mut other_results] {
+            results.sort_by(|a, b| {
+                b.score
+                    .total_cmp(&a.score)
+                    .then_with(|| (&a.text, &a.pinyin_segments).cmp(&(&b.text, &b.pinyin_segments)))
+            });
+            results.dedup_by(|a, b| a.text == b.text && a.pinyin_segments == b.pinyin_segments);
+        }
+
+        // 合并结果：优先结果在前，其他结果在后
+        consonant_results.extend(priority_results);
+        consonant_results.extend(other_results);
+
+        consonant_results
+    }
+
+    /// 判断是否为声母本身的候选项
+    /// 例如：输入 h，hat、hax、ha、hap 等是声母本身的候选项
+    /// 而 hmat、hmax 等不是
+    fn is_consonant_itself_candidate(consonant: &str, pinyin: &str) -> bool {
+        if consonant.len() == 1 {
+            // 单字母声母：检查拼音是否以该声母开头且第二个字符是元音
+            if let Some(second_char) = pinyin.chars().nth(1) {
+                matches!(second_char, 'a' | 'e' | 'i' | 'o' | 'u')
+            } else {
+                false
+            }
+        } else {
+            // 多字母声母：直接匹配
+            pinyin.starts_with(consonant)
+        }
+    }
+}
@@ -0,0 +1,463 @@
+use crate::candidates::Candidate;
+use crate::ime::YiIME;
+use crate::learning::UserHistory;
+use crate::options::ImeOptions;
+use crate::segmentation::is_syllable_delimiter;
+use crate::session::Session;
+use std::sync::Arc;
+
+/// 输出形式：决定提交候选项时彝文与拼音如何组合
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InputMode {
+    #[default]
+    YiOnly,           // 彝文
+    PinyinYi,         // 拼音+彝文
+    PinyinWithYi,     // 拼音（彝文）
+    YiWithPinyin,     // 彝文（拼音）
+    HtmlRuby,         // HTML注音
+}
+
+/// 与平台无关的按键
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    /// 字母、音节分隔符（`'` 或 `-`）或标点字符（未经 Shift 转换的原始按键字符）
+    Char(char),
+    /// 退格键
+    Backspace,
+    /// 空格键：提交当前选中的候选项
+    Space,
+    /// ESC键：取消输入
+    Escape,
+    /// 数字键选择候选项（从 1 开始）
+    Select(usize),
+}
+
+/// 修饰键状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+/// 组合器处理按键后要求前端执行的动作
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// 更新输入框中显示的拼音
+    UpdatePreedit(String),
+    /// 更新候选列表
+    UpdateCandidates(Vec<Candidate>),
+    /// 向目标程序提交文本
+    Commit(String),
+    /// 输入结束，隐藏输入框和候选窗口
+    Hide,
+}
+
+/// 输入组合状态机：维护拼音缓冲区和候选项，把按键翻译为前端动作
+///
+/// 拼音缓冲区保存在输入会话中，逐字扩展和截短分词网格，
+/// 预编辑文本、合法性检查和候选项都来自同一次分词。
+#[derive(Debug, Clone)]
+pub struct Composer {
+    engine: Arc<YiIME>,
+    session: Session,
+    candidates: Vec<Candidate>,
+    selected_index: usize,
+    input_mode: InputMode,
+    /// 用户输入历史，用于调整候选项顺序
+    history: UserHistory,
+}
+
+impl Composer {
+    pub fn new(engine: Arc<YiIME>) -> Self {
+        Self {
+            engine,
+            session: Session::new(),
+            candidates: Vec::new(),
+            selected_index: 0,
+            input_mode: InputMode::default(),
+            history: UserHistory::new(),
+        }
+    }
+
+    /// 使用指定的输入历史（例如从用户数据文件打开的历史）
+    pub fn with_history(mut self, history: UserHistory) -> Self {
+        self.history = history;
+        self
+    }
+
+    /// 用户输入历史
+    pub fn history(&self) -> &UserHistory {
+        &self.history
+    }
+
+    /// 用户输入历史（用于重置、导出或忘记记录）
+    pub fn history_mut(&mut self) -> &mut UserHistory {
+        &mut self.history
+    }
+
+    /// 当前输入缓冲区中的拼音
+    pub fn preedit(&self) -> &str {
+        self.session.input()
+    }
+
+    /// 当前输入会话（包含分词结果）
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// 当前候选项列表
+    pub fn candidates(&self) -> &[Candidate] {
+        &self.candidates
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.session.is_empty()
+    }
+
+    pub fn input_mode(&self) -> InputMode {
+        self.input_mode
+    }
+
+    pub fn set_input_mode(&mut self, mode: InputMode) {
+        self.input_mode = mode;
+    }
+
+    /// 引擎的可选行为
+    pub fn options(&self) -> &ImeOptions {
+        &self.engine.options
+    }
+
+    /// 修改引擎的可选行为（如无声调输入和拼写纠错的开关），正在输入的拼音按新设置重新分词
+    pub fn set_options(&mut self, options: ImeOptions) {
+        if self.engine.options == options {
+            return;
+        }
+        Arc::make_mut(&mut self.engine).options = options;
+        if !self.session.is_empty() {
+            self.session = Session::with_input(&self.engine, self.session.input());
+            self.refresh();
+        }
+    }
+
+    /// 清空输入缓冲区和候选项
+    pub fn reset(&mut self) {
+        self.session.clear();
+        self.candidates.clear();
+        self.selected_index = 0;
+    }
+
+    /// 处理一次按键，返回前端需要执行的动作；返回空列表表示按键未被处理
+    pub fn handle_key(&mut self, key: Key, modifiers: Modifiers) -> Vec<Action> {
+        // 组合键交给系统处理
+        if modifiers.ctrl || modifiers.alt {
+            return Vec::new();
+        }
+
+        match key {
+            Key::Backspace => {
+                if self.session.is_empty() {
+                    return Vec::new();
+                }
+                self.session.pop(&self.engine);
+                if self.session.is_empty() {
+                    self.reset();
+                    return vec![Action::Hide];
+                }
+                self.refresh()
+            }
+            Key::Select(number) => {
+                if self.session.is_empty() || number == 0 || number > self.candidates.len() {
+                    return Vec::new();
+                }
+                let selected = self.candidates[number - 1].clone();
+                self.commit(&selected, "")
+            }
+            Key::Space => {
+                match self.candidates.get(self.selected_index).cloned() {
+                    Some(selected) if !self.session.is_empty() => self.commit(&selected, ""),
+                    _ => Vec::new(),
+                }
+            }
+            Key::Escape => {
+                if self.session.is_empty() {
+                    return Vec::new();
+                }
+                self.reset();
+                vec![Action::Hide]
+            }
+            Key::Char(ch) if ch.is_ascii_alphabetic() => {
+                // 直接添加字符到输入缓冲区
+                self.session.push(&self.engine, ch.to_ascii_lowercase());
+                self.refresh()
+            }
+            Key::Char(ch) if ch == self.engine.options.iteration_key && !ch.is_ascii_alphabetic() => {
+                // 不是字母的替字符号按键：只能接在拼音之后
+                if self.session.is_empty() {
+                    return Vec::new();
+                }
+                self.session.push(&self.engine, ch);
+                self.refresh()
+            }
+            Key::Char(ch) if is_syllable_delimiter(ch) && !modifiers.shift => {
+                // 音节分隔符只能出现在拼音之间，缓冲区为空时交给系统处理
+                if self.session.is_empty() {
+                    return Vec::new();
+                }
+                self.session.push(&self.engine, ch);
+                self.refresh()
+            }
+            Key::Char(ch) => {
+                // 处理特殊标点符号按键（只有在缓冲区不为空时）
+                if self.session.is_empty() {
+                    return Vec::new();
+                }
+                let Some(punctuation) = punctuation_for(ch, modifiers.shift) else {
+                    return Vec::new();
+                };
+                // 先提交第一个候选词，再跟随标点
+                match self.candidates.get(self.selected_index).cloned() {
+                    Some(selected) => self.commit(&selected, punctuation),
+                    None => Vec::new(),
+                }
+            }
+        }
+    }
+
+    /// 重新计算候选项并通知前端刷新
+    fn refresh(&mut self) -> Vec<Action> {
+        let candidates = self.engine.session_candidates(&self.session);
+        self.candidates = self.history.rerank(self.session.input(), candidates);
+        self.selected_index = 0;
+        vec![
+            Action::UpdatePreedit(self.session.input().to_string()),
+            Action::UpdateCandidates(self.candidates.clone()),
+        ]
+    }
+
+    /// 提交候选项（可附带标点），并结束本次输入
+    fn commit(&mut self, candidate: &Candidate, suffix: &str) -> Vec<Action> {
+        // 根据输入模式格式化文本
+        let formatted_text = format_text_by_mode(self.input_mode, &candidate.text, &candidate.pinyin());
+
+        self.history.record(self.session.input(), candidate);
+        self.reset();
+        vec![Action::Commit(format!("{}{}", formatted_text, suffix)), Action::Hide]
+    }
+}
+
+/// 标点符号映射表：按键字符（及 Shift 状态）-> 输出的标点
+pub fn punctuation_for(key: char, shift: bool) -> Option<&'static str> {
+    let punctuation = match (key, shift) {
+        ('[', false) => "【",
+        ('[', true) => "{",
+        (']', false) => "】",
+        (']', true) => "}",
+        ('\\', false) => "、",
+        ('\\', true) => "|",
+        (';', false) => "；",
+        (';', true) => "：",
+        (',', false) => "，",
+        (',', true) => "《",
+        ('.', false) => "。",
+        ('.', true) => "》",
+        _ => return None,
+    };
+    Some(punctuation)
+}
+
+/// 根据输出形式组合彝文与拼音
+pub fn format_text_by_mode(mode: InputMode, yi_text: &str, pinyin: &str) -> String {
+    match mode {
+        InputMode::YiOnly => yi_text.to_string(),
+        InputMode::PinyinYi => {
+            // 拼音+彝文：先输入拼音（音节间用空格代替短横线），跟随一个空格，再跟随彝文
+            let formatted_pinyin = pinyin.replace('-', " ");
+            format!("{} {}", formatted_pinyin, yi_text)
+        },
+        InputMode::PinyinWithYi => {
+            // 拼音（彝文）：先输入拼音，小括号内有彝文
+            let formatted_pinyin = pinyin.replace('-', " ");
+            format!("{}（{}）", formatted_pinyin, yi_text)
+        },
+        InputMode::YiWithPinyin => {
+            // 彝文（拼音）：先输入彝文，小括号内有拼音
+            let formatted_pinyin = pinyin.replace('-', " ");
+            format!("{}（{}）", yi_text, formatted_pinyin)
+        },
+        InputMode::HtmlRuby => {
+            // HTML注音：每个彝文字符都用ruby标签包装
+            format_as_html_ruby(yi_text, pinyin)
+        },
+    }
+}
+
+fn format_as_html_ruby(yi_text: &str, pinyin: &str) -> String {
+    let pinyin_parts: Vec<&str> = pinyin.split('-').collect();
+
+    let mut result = String::new();
+
+    for (i, yi_char) in yi_text.chars().enumerate() {
+        let corresponding_pinyin = pinyin_parts.get(i).unwrap_or(&"");
+        result.push_str(&format!(
+            "<ruby>{}<rp>(</rp><rt>{}</rt><rp>)</rp></ruby>",
+            yi_char, corresponding_pinyin
+        ));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidates::CandidateKind;
+
+    fn composer() -> Composer {
+        Composer::new(Arc::new(YiIME::with_builtin_dictionaries().unwrap()))
+    }
+
+    /// 依次输入字符，不带修饰键
+    fn type_keys(composer: &mut Composer, keys: &str) {
+        for ch in keys.chars() {
+            composer.handle_key(Key::Char(ch), Modifiers::default());
+        }
+    }
+
+    #[test]
+    fn letters_update_preedit_and_candidates() {
+        let mut composer = composer();
+        let actions = composer.handle_key(Key::Char('X'), Modifiers { shift: true, ..Modifiers::default() });
+        assert_eq!(actions[0], Action::UpdatePreedit("x".to_string()));
+        assert!(matches!(&actions[1], Action::UpdateCandidates(candidates) if !candidates.is_empty()));
+        type_keys(&mut composer, "iep");
+        assert_eq!(composer.preedit(), "xiep");
+        assert_eq!(composer.candidates()[0].text, "ꑤ");
+    }
+
+    #[test]
+    fn backspace_to_empty_hides() {
+        let mut composer = composer();
+        type_keys(&mut composer, "nu");
+        let actions = composer.handle_key(Key::Backspace, Modifiers::default());
+        assert_eq!(actions[0], Action::UpdatePreedit("n".to_string()));
+        assert_eq!(composer.handle_key(Key::Backspace, Modifiers::default()), [Action::Hide]);
+        assert!(composer.is_empty());
+        assert!(composer.candidates().is_empty());
+        // 缓冲区为空时退格键交给系统处理
+        assert!(composer.handle_key(Key::Backspace, Modifiers::default()).is_empty());
+    }
+
+    #[test]
+    fn select_commits_numbered_candidate() {
+        let mut composer = composer();
+        type_keys(&mut composer, "nuo");
+        let second = composer.candidates()[1].text.clone();
+        let actions = composer.handle_key(Key::Select(2), Modifiers::default());
+        assert_eq!(actions, [Action::Commit(second), Action::Hide]);
+        assert!(composer.is_empty());
+    }
+
+    #[test]
+    fn select_out_of_range_is_ignored() {
+        let mut composer = composer();
+        type_keys(&mut composer, "nuo");
+        let count = composer.candidates().len();
+        assert!(composer.handle_key(Key::Select(0), Modifiers::default()).is_empty());
+        assert!(composer.handle_key(Key::Select(count + 1), Modifiers::default()).is_empty());
+        assert_eq!(composer.preedit(), "nuo");
+        // 没有输入时数字键交给系统处理
+        composer.reset();
+        assert!(composer.handle_key(Key::Select(1), Modifiers::default()).is_empty());
+    }
+
+    #[test]
+    fn space_without_candidates_keeps_input() {
+        let mut composer = composer();
+        type_keys(&mut composer, "qqq");
+        assert!(composer.candidates().is_empty());
+        assert!(composer.handle_key(Key::Space, Modifiers::default()).is_empty());
+        assert_eq!(composer.preedit(), "qqq");
+        // 缓冲区为空时空格交给系统处理
+        composer.reset();
+        assert!(composer.handle_key(Key::Space, Modifiers::default()).is_empty());
+    }
+
+    #[test]
+    fn space_commits_first_candidate() {
+        let mut composer = composer();
+        type_keys(&mut composer, "xiep");
+        let actions = composer.handle_key(Key::Space, Modifiers::default());
+        assert_eq!(actions, [Action::Commit("ꑤ".to_string()), Action::Hide]);
+    }
+
+    #[test]
+    fn punctuation_commits_with_candidate() {
+        let mut composer = composer();
+        type_keys(&mut composer, "xiep");
+        let actions = composer.handle_key(Key::Char('.'), Modifiers::default());
+        assert_eq!(actions, [Action::Commit("ꑤ。".to_string()), Action::Hide]);
+
+        type_keys(&mut composer, "xiep");
+        let actions = composer.handle_key(Key::Char(','), Modifiers { shift: true, ..Modifiers::default() });
+        assert_eq!(actions, [Action::Commit("ꑤ《".to_string()), Action::Hide]);
+
+        // 缓冲区为空时标点交给系统处理
+        assert!(composer.handle_key(Key::Char('.'), Modifiers::default()).is_empty());
+    }
+
+    #[test]
+    fn modifier_combinations_pass_through() {
+        let mut composer = composer();
+        type_keys(&mut composer, "nuo");
+        let ctrl = Modifiers { ctrl: true, ..Modifiers::default() };
+        let alt = Modifiers { alt: true, ..Modifiers::default() };
+        assert!(composer.handle_key(Key::Char('a'), ctrl).is_empty());
+        assert!(composer.handle_key(Key::Backspace, alt).is_empty());
+        assert!(composer.handle_key(Key::Space, ctrl).is_empty());
+        assert_eq!(composer.preedit(), "nuo");
+    }
+
+    #[test]
+    fn escape_cancels_input() {
+        let mut composer = composer();
+        type_keys(&mut composer, "nuo");
+        assert_eq!(composer.handle_key(Key::Escape, Modifiers::default()), [Action::Hide]);
+        assert!(composer.is_empty());
+    }
+
+    #[test]
+    fn options_apply_to_pending_input() {
+        let mut composer = composer();
+        type_keys(&mut composer, "ba");
+        assert!(composer.candidates().iter().all(|c| c.pinyin() == "ba" || c.kind == CandidateKind::Completion));
+
+        let mut options = composer.options().clone();
+        options.toneless = true;
+        composer.set_options(options);
+        assert_eq!(composer.preedit(), "ba");
+        let tones: Vec<String> = composer.candidates().iter().map(Candidate::pinyin).collect();
+        assert_eq!(tones, ["ba", "bat", "bax", "bap"]);
+    }
+
+    #[test]
+    fn symbol_iteration_key() {
+        let mut engine = YiIME::with_builtin_dictionaries().unwrap();
+        engine.options.iteration_key = ';';
+        let mut composer = Composer::new(Arc::new(engine));
+        // 缓冲区为空时按键交给系统处理
+        assert!(composer.handle_key(Key::Char(';'), Modifiers::default()).is_empty());
+        type_keys(&mut composer, "nuo;su");
+        assert_eq!(composer.preedit(), "nuo;su");
+        assert_eq!(composer.candidates()[0].text, "ꆈꀕꌠ");
+    }
+
+    #[test]
+    fn commit_uses_input_mode() {
+        let mut composer = composer();
+        composer.set_input_mode(InputMode::YiWithPinyin);
+        type_keys(&mut composer, "nuosu");
+        let actions = composer.handle_key(Key::Space, Modifiers::default());
+        assert_eq!(actions[0], Action::Commit("ꆈꌠ（nuo su）".to_string()));
+    }
+}
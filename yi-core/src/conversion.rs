@@ -0,0 +1,673 @@
+use crate::candidates::{Candidate, CandidateKind, CandidateSource};
+use crate::ime::{YiIME, ITERATION_MARK, RADICAL_SCORE};
+use crate::options::Reduplication;
+use crate::segmentation::SegmentResult;
+use crate::session::Session;
+use crate::toneless::TONELESS_WEIGHT;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::time::Instant;
+
+/// smart_convert 一次返回的候选项数量上限
+pub const CONVERSION_LIMIT: usize = 50;
+
+/// 按分数从高到低惰性枚举一个分词方案对应的所有彝文组合
+///
+/// 每组候选字按权重从高到低排列，组合分数为各字权重之积；
+/// 分数相同时按各组下标的字典序输出，因此结果顺序是确定的。
+/// 已知词语可以单独给出分数（见 [`YiCombinations::with_phrases`]），按该分数插入到结果中。
+#[derive(Debug, Clone)]
+pub struct YiCombinations {
+    groups: Vec<Vec<(String, f32)>>,
+    heap: BinaryHeap<RankedIndices>,
+    seen: HashSet<Vec<usize>>,
+    deadline: Option<Instant>,
+    /// 已知词语及其分数，按分数从低到高排列，便于从末尾取出
+    phrases: Vec<(String, f32)>,
+    phrase_texts: HashSet<String>,
+    /// 已经枚举出、尚未输出的普通组合
+    pending: Option<(String, f32)>,
+}
+
+#[derive(Debug, Clone)]
+struct RankedIndices {
+    score: f32,
+    indices: Vec<usize>,
+}
+
+impl PartialEq for RankedIndices {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for RankedIndices {}
+
+impl PartialOrd for RankedIndices {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedIndices {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // 分数高者优先，分数相同时下标字典序小者优先
+        self.score
+            .total_cmp(&other.score)
+            .then_with(|| other.indices.cmp(&self.indices))
+    }
+}
+
+impl YiCombinations {
+    /// 由每个音节的候选字及其权重构建组合枚举器
+    pub fn new(mut groups: Vec<Vec<(String, f32)>>) -> Self {
+        for group in &mut groups {
+            group.sort_by(|a, b| b.1.total_cmp(&a.1));
+        }
+
+        let mut combinations = Self {
+            groups,
+            heap: BinaryHeap::new(),
+            seen: HashSet::new(),
+            deadline: None,
+            phrases: Vec::new(),
+            phrase_texts: HashSet::new(),
+            pending: None,
+        };
+
+        if combinations.groups.iter().all(|group| !group.is_empty()) {
+            let start = vec![0; combinations.groups.len()];
+            combinations.push(start);
+        }
+        combinations
+    }
+
+    /// 超过截止时间后不再产生新的组合
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    /// 指定已知词语的分数：这些词语按给定分数输出，不再按逐字权重之积输出
+    pub fn with_phrases(mut self, mut phrases: Vec<(String, f32)>) -> Self {
+        // 分数相同时文本小者先输出
+        phrases.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| b.0.cmp(&a.0)));
+        phrases.dedup_by(|a, b| a.0 == b.0);
+        self.phrase_texts = phrases.iter().map(|(text, _)| text.clone()).collect();
+        self.phrases = phrases;
+        self
+    }
+
+    /// 取出下一个普通组合（不含已知词语）
+    fn next_combination(&mut self) -> Option<(String, f32)> {
+        loop {
+            let RankedIndices { score, indices } = self.heap.pop()?;
+
+            // 后继组合：任意一个音节换成下一个候选字
+            for position in 0..indices.len() {
+                if indices[position] + 1 < self.groups[position].len() {
+                    let mut next = indices.clone();
+                    next[position] += 1;
+                    self.push(next);
+                }
+            }
+
+            let text: String = indices
+                .iter()
+                .zip(&self.groups)
+                .map(|(&i, group)| group[i].0.as_str())
+                .collect();
+            if !self.phrase_texts.contains(&text) {
+                return Some((text, score));
+            }
+        }
+    }
+
+    fn push(&mut self, indices: Vec<usize>) {
+        if !self.seen.insert(indices.clone()) {
+            return;
+        }
+        let score = indices
+            .iter()
+            .zip(&self.groups)
+            .map(|(&i, group)| group[i].1)
+            .product();
+        self.heap.push(RankedIndices { score, indices });
+    }
+}
+
+impl Iterator for YiCombinations {
+    /// 彝文组合及其分数
+    type Item = (String, f32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None;
+        }
+
+        if self.pending.is_none() {
+            self.pending = self.next_combination();
+        }
+
+        let phrase_first = match (self.phrases.last(), &self.pending) {
+            (Some(phrase), Some(pending)) => phrase.1 >= pending.1,
+            (phrase, _) => phrase.is_some(),
+        };
+        if phrase_first {
+            self.phrases.pop()
+        } else {
+            self.pending.take()
+        }
+    }
+}
+
+/// 一个分词方案的候选项流
+#[derive(Debug, Clone)]
+struct ConversionStream {
+    combinations: YiCombinations,
+    pinyin_segments: Vec<String>,
+    kind: CandidateKind,
+    source: CandidateSource,
+    confidence: f32,
+    /// 分词方案的排名，合并各流时先于分数比较
+    rank: SegmentRank,
+    /// 替字符号 ꀕ 的音节序号：读音随前一个字变化
+    iterations: Vec<usize>,
+    /// 无声调输入时各音节中声调与输入不同的字 -> 实际读音
+    readings: Vec<HashMap<String, String>>,
+    /// 整个方案为词库中的一个词时，词 -> 释义
+    glosses: HashMap<String, String>,
+    /// 与前一个音节读音相同、可以写成替字符号 ꀕ 的音节序号（不在词库的词内）
+    reduplications: Vec<usize>,
+    /// 是否先给出替字符号形式
+    prefer_mark: bool,
+    /// 同一组合的另一种写法，紧接着输出
+    pending: Option<Candidate>,
+}
+
+impl ConversionStream {
+    fn new(
+        ime: &YiIME,
+        result: &SegmentResult,
+        kind: CandidateKind,
+        source: CandidateSource,
+    ) -> Self {
+        let readings = if ime.options.toneless {
+            result
+                .segments
+                .iter()
+                .zip(&result.yi_chars)
+                .map(|(segment, yi_chars)| {
+                    yi_chars
+                        .iter()
+                        .filter_map(|yi_char| {
+                            let reading = ime.reading_for(yi_char, segment)?;
+                            (reading != *segment).then(|| (yi_char.clone(), reading))
+                        })
+                        .collect()
+                })
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let glosses = if result.words == [(0, result.segments.len())] {
+            ime.lexicon
+                .words_for(&result.segments)
+                .into_iter()
+                .filter_map(|word| Some((word.text.clone(), word.gloss.clone()?)))
+                .collect()
+        } else {
+            HashMap::new()
+        };
+
+        // 只处理用户确实连续输入了两次的音节，纠错得到的重复不算
+        let in_word = |index: usize| result.words.iter().any(|&(start, end)| (start..end).contains(&index));
+        let repeatable = if result.corrected { 0 } else { result.segments.len() };
+        let reduplications = (1..repeatable)
+            .filter(|&i| result.segments[i] == result.segments[i - 1])
+            .filter(|&i| !result.iterations.contains(&i) && !in_word(i) && !in_word(i - 1))
+            .collect();
+
+        Self {
+            combinations: ime.yi_combinations(result),
+            pinyin_segments: result.segments.clone(),
+            kind,
+            source,
+            confidence: result.confidence,
+            rank: SegmentRank::of(result),
+            iterations: result.iterations.clone(),
+            readings,
+            glosses,
+            reduplications,
+            prefer_mark: ime.options.reduplication == Reduplication::PreferMark,
+            pending: None,
+        }
+    }
+
+    /// 把与前一个字相同的重复字写成替字符号，没有这样的字时返回 None
+    fn with_marks(&self, text: &str) -> Option<String> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut marked = chars.clone();
+        for &index in &self.reduplications {
+            if chars.get(index).is_some_and(|&ch| ch == chars[index - 1]) {
+                marked[index] = ITERATION_MARK;
+            }
+        }
+        (marked != chars).then(|| marked.into_iter().collect())
+    }
+
+    /// 组合中各字的实际读音
+    fn pinyin_segments_for(&self, text: &str) -> Vec<String> {
+        let mut pinyin_segments = self.pinyin_segments.clone();
+        for ((segment, yi_char), readings) in pinyin_segments.iter_mut().zip(text.chars()).zip(&self.readings) {
+            if let Some(reading) = readings.get(yi_char.encode_utf8(&mut [0; 4]) as &str) {
+                *segment = reading.clone();
+            }
+        }
+        for &index in &self.iterations {
+            if index > 0 {
+                pinyin_segments[index] = pinyin_segments[index - 1].clone();
+            }
+        }
+        pinyin_segments
+    }
+
+    fn next_candidate(&mut self) -> Option<Candidate> {
+        if let Some(pending) = self.pending.take() {
+            return Some(pending);
+        }
+
+        let (text, score) = self.combinations.next()?;
+        let pinyin_segments = self.pinyin_segments_for(&text);
+        let gloss = self.glosses.get(&text).cloned();
+        let marked = self.with_marks(&text);
+        let candidate = Candidate::new(
+            text,
+            pinyin_segments,
+            self.kind,
+            self.confidence * score,
+            self.source,
+        ).with_gloss(gloss);
+
+        // 重复的字可以写成替字符号：两种写法分数相同，按设置决定先后
+        let Some(marked) = marked else {
+            return Some(candidate);
+        };
+        let alternative = Candidate { text: marked, gloss: None, ..candidate.clone() };
+        if self.prefer_mark {
+            self.pending = Some(candidate);
+            Some(alternative)
+        } else {
+            self.pending = Some(alternative);
+            Some(candidate)
+        }
+    }
+}
+
+/// 分词方案的排名，与分词网格的排序一致：编辑代价小的在前（精确方案的代价为 0），
+/// 词库中的词覆盖的输入越多越靠前。加载语言模型后，逐字方案的分数可能高于词的方案，
+/// 只按分数合并会打乱分词结果的顺序。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct SegmentRank {
+    edit_cost: Reverse<RankedScore>,
+    word_chars: usize,
+}
+
+impl SegmentRank {
+    fn of(result: &SegmentResult) -> Self {
+        let word_chars = result
+            .words
+            .iter()
+            .flat_map(|&(start, end)| &result.segments[start..end])
+            .map(|segment| segment.chars().count())
+            .sum();
+        Self { edit_cost: Reverse(RankedScore(result.edit_cost)), word_chars }
+    }
+}
+
+/// 智能转换的结果：把所有分词方案的组合按方案排名和分数合并，惰性地逐个产生候选项
+#[derive(Debug, Clone)]
+pub struct Conversions {
+    /// 必须排在最前面的候选项，按顺序输出
+    front: VecDeque<Candidate>,
+    streams: Vec<ConversionStream>,
+    /// 每个流已取出但尚未输出的候选项
+    heads: Vec<Option<Candidate>>,
+    /// (方案排名, 分数, 流序号)：排名相同时分数高的优先，分数也相同时序号小的流优先
+    heap: BinaryHeap<(SegmentRank, RankedScore, Reverse<usize>)>,
+    /// 已输出的 (彝文, 拼音)：词的方案和逐字的方案可能给出相同的候选项
+    seen: HashSet<(String, Vec<String>)>,
+    deadline: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RankedScore(f32);
+
+impl Eq for RankedScore {}
+
+impl PartialOrd for RankedScore {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for RankedScore {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+impl Conversions {
+    fn new(front: Vec<Candidate>, mut streams: Vec<ConversionStream>) -> Self {
+        let mut heads = Vec::with_capacity(streams.len());
+        let mut heap = BinaryHeap::new();
+        for (index, stream) in streams.iter_mut().enumerate() {
+            let head = stream.next_candidate();
+            if let Some(candidate) = &head {
+                heap.push((stream.rank, RankedScore(candidate.score), Reverse(index)));
+            }
+            heads.push(head);
+        }
+        Self {
+            front: front.into(),
+            streams,
+            heads,
+            heap,
+            seen: HashSet::new(),
+            deadline: None,
+        }
+    }
+
+    /// 超过截止时间后不再产生新的候选项
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        for stream in &mut self.streams {
+            stream.combinations.deadline = Some(deadline);
+        }
+        self
+    }
+}
+
+impl Conversions {
+    /// 按顺序取出下一个候选项（可能与已输出的重复）
+    fn next_merged(&mut self) -> Option<Candidate> {
+        if let Some(front) = self.front.pop_front() {
+            return Some(front);
+        }
+        if self.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            return None;
+        }
+
+        let (rank, _, Reverse(index)) = self.heap.pop()?;
+        let candidate = self.heads[index].take();
+        self.heads[index] = self.streams[index].next_candidate();
+        if let Some(next) = &self.heads[index] {
+            self.heap.push((rank, RankedScore(next.score), Reverse(index)));
+        }
+        candidate
+    }
+}
+
+impl Iterator for Conversions {
+    type Item = Candidate;
+
+    fn next(&mut self) -> Option<Candidate> {
+        loop {
+            let candidate = self.next_merged()?;
+            if self.seen.insert((candidate.text.clone(), candidate.pinyin_segments.clone())) {
+                return Some(candidate);
+            }
+        }
+    }
+}
+
+impl YiIME {
+    /// 按分数从高到低惰性枚举分词结果对应的彝文组合
+    ///
+    /// 每个字的权重由使用频率决定（无声调匹配的字再乘以折扣）；
+    /// 词语频率表中的词语在逐字权重之积的基础上按词语频率加分。
+    /// 方案中词库的词所覆盖的音节合并为一组，只由词库中的词组成。
+    pub fn yi_combinations(&self, segment_result: &SegmentResult) -> YiCombinations {
+        if !segment_result.words.is_empty() {
+            return self.word_combinations(segment_result);
+        }
+
+        let groups = self.char_groups(segment_result);
+        let phrases = self
+            .phrases_for(&segment_result.yi_chars)
+            .into_iter()
+            .filter_map(|phrase| {
+                let bonus = 1.0 + self.phrase_weight(&phrase)?;
+                let score: f32 = phrase
+                    .chars()
+                    .zip(&groups)
+                    .map(|(ch, group)| {
+                        group
+                            .iter()
+                            .find(|(yi_char, _)| yi_char.chars().eq(std::iter::once(ch)))
+                            .map_or(0.0, |(_, weight)| *weight)
+                    })
+                    .product();
+                Some((phrase, score * bonus))
+            })
+            .collect();
+
+        YiCombinations::new(groups).with_phrases(phrases)
+    }
+
+    /// 包含词库中的词的方案：词所在的音节只能是词库中的词，其余音节逐字组合
+    fn word_combinations(&self, segment_result: &SegmentResult) -> YiCombinations {
+        let mut char_groups = self.char_groups(segment_result).into_iter();
+        let mut groups = Vec::new();
+        let mut index = 0;
+        while index < segment_result.segments.len() {
+            let word = segment_result.words.iter().find(|(start, _)| *start == index);
+            match word {
+                Some(&(start, end)) => {
+                    let words = self.lexicon.words_for(&segment_result.segments[start..end]);
+                    groups.push(words.into_iter().map(|word| (word.text.clone(), self.word_weight(word))).collect());
+                    char_groups.nth(end - start - 1);
+                    index = end;
+                }
+                None => {
+                    groups.extend(char_groups.next());
+                    index += 1;
+                }
+            }
+        }
+        YiCombinations::new(groups)
+    }
+
+    /// 每个音节的候选字及其权重
+    fn char_groups(&self, segment_result: &SegmentResult) -> Vec<Vec<(String, f32)>> {
+        segment_result
+            .yi_chars
+            .iter()
+            .zip(&segment_result.segments)
+            .enumerate()
+            .map(|(index, (group, segment))| {
+                // 替字符号只有一种写法，不影响组合的分数
+                if segment_result.iterations.contains(&index) {
+                    return vec![(ITERATION_MARK.to_string(), 1.0)];
+                }
+                group
+                    .iter()
+                    .map(|yi_char| {
+                        // 无声调输入时，声调与输入不同的字排在声调完全匹配的字后面
+                        let exact = self.dictionary.get(yi_char).is_some_and(|r| r.contains(segment));
+                        let tone_weight = if exact { 1.0 } else { TONELESS_WEIGHT };
+                        (yi_char.clone(), tone_weight * self.char_weight(yi_char))
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // 将分词结果转换为彝文（按分数排序，最多 CONVERSION_LIMIT 个）
+    pub fn convert_to_yi(&self, segment_result: &SegmentResult) -> Vec<String> {
+        self.yi_combinations(segment_result)
+            .take(CONVERSION_LIMIT)
+            .map(|(text, _)| text)
+            .collect()
+    }
+
+    /// 分词结果最好的写法，与该方案的第一个候选项相同（重复的字按设置写成替字符号或重复字）
+    pub(crate) fn best_conversion(&self, segment_result: &SegmentResult) -> Option<String> {
+        let source = Self::segmentation_source(segment_result);
+        let mut stream = ConversionStream::new(self, segment_result, CandidateKind::Phrase, source);
+        stream.next_candidate().map(|candidate| candidate.text)
+    }
+
+    // 智能转换：输入拼音序列，输出按分数排序的彝文组合（包含部首）
+    pub fn smart_convert(&self, input: &str) -> Vec<Candidate> {
+        self.smart_convert_iter(input).take(CONVERSION_LIMIT).collect()
+    }
+
+    /// 智能转换的惰性版本：调用者可以按需翻页，或用 take / with_deadline 限制工作量
+    pub fn smart_convert_iter(&self, input: &str) -> Conversions {
+        self.session_conversions(&Session::with_input(self, input))
+    }
+
+    /// 根据输入会话进行智能转换，使用会话中已经计算好的分词结果
+    pub fn session_conversions(&self, session: &Session) -> Conversions {
+        let input = session.input();
+        let mut streams = Vec::new();
+        
+        for result in session.segments() {
+            let kind = if result.segments.len() == 1 {
+                CandidateKind::Syllable
+            } else {
+                CandidateKind::Phrase
+            };
+            
+            // 检查是否应该添加部首候选项
+            // 如果只有一个音节，检查是否有对应的部首
+            if self.should_add_radical(input, std::slice::from_ref(result)) && result.segments.len() == 1 && !result.corrected {
+                let radicals = self.get_radical_candidates(&result.segments[0]);
+                if !radicals.is_empty() {
+                    // 分数相同的部首按区块顺序输出
+                    let group = radicals.iter().map(|radical| (radical.clone(), 1.0)).collect();
+                    streams.push(ConversionStream {
+                        combinations: YiCombinations::new(vec![group]),
+                        pinyin_segments: result.segments.clone(),
+                        kind: CandidateKind::Radical,
+                        source: CandidateSource::RadicalDictionary,
+                        confidence: result.confidence,
+                        rank: SegmentRank::of(result),
+                        iterations: Vec::new(),
+                        readings: Vec::new(),
+                        glosses: HashMap::new(),
+                        reduplications: Vec::new(),
+                        prefer_mark: false,
+                        pending: None,
+                    });
+                }
+            }
+            
+            streams.push(ConversionStream::new(self, result, kind, Self::segmentation_source(result)));
+        }
+        
+        // 用户词库中与输入完整匹配的词语排在最前面
+        let mut front = self.user_phrase_candidates(input);
+
+        // 特殊处理：如果输入是单音节且在部首字典中，确保部首出现在候选中
+        if !input.contains(char::is_whitespace) {
+            let has_radical = streams.iter().any(|stream| stream.kind == CandidateKind::Radical);
+            if !has_radical {
+                front.extend(self.get_radical_candidates(input).iter().map(|radical| Candidate::new(
+                    radical.clone(),
+                    vec![input.to_string()],
+                    CandidateKind::Radical,
+                    RADICAL_SCORE,
+                    CandidateSource::RadicalDictionary,
+                )));
+            }
+        }
+        
+        Conversions::new(front, streams)
+    }
+
+    /// 分词方案产生的候选项的来源
+    fn segmentation_source(result: &SegmentResult) -> CandidateSource {
+        if result.corrected {
+            CandidateSource::Correction
+        } else if result.words == [(0, result.segments.len())] {
+            CandidateSource::Lexicon
+        } else {
+            CandidateSource::Segmentation
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ime() -> YiIME {
+        YiIME::with_builtin_dictionaries().unwrap()
+    }
+
+    fn texts(candidates: &[Candidate]) -> Vec<&str> {
+        candidates.iter().map(|candidate| candidate.text.as_str()).collect()
+    }
+
+    #[test]
+    fn converts_segmented_input() {
+        let ime = ime();
+        let candidates = ime.smart_convert("shyrxnuo");
+        assert_eq!(texts(&candidates), ["ꏄꆈ", "ꏄꆓꀑ"]);
+        assert_eq!(candidates[0].pinyin_segments, ["shyrx", "nuo"]);
+        assert_eq!(candidates[0].kind, CandidateKind::Phrase);
+        assert_eq!(candidates[0].source, CandidateSource::Segmentation);
+    }
+
+    #[test]
+    fn combinations_in_score_order() {
+        let combinations = YiCombinations::new(vec![
+            vec![("ꀀ".to_string(), 0.5), ("ꀁ".to_string(), 1.0)],
+            vec![("ꀂ".to_string(), 1.0), ("ꀃ".to_string(), 0.2)],
+        ]);
+        let all: Vec<String> = combinations.map(|(text, _)| text).collect();
+        assert_eq!(all, ["ꀁꀂ", "ꀀꀂ", "ꀁꀃ", "ꀀꀃ"]);
+    }
+
+    #[test]
+    fn reduplication_order_follows_option() {
+        let mut ime = ime();
+        assert_eq!(texts(&ime.smart_convert("bbubbu")), ["ꁮꁮ", "ꁮꀕ"]);
+        assert_eq!(ime.transliterate_text("bbubbu"), "ꁮꁮ");
+
+        ime.options.reduplication = Reduplication::PreferMark;
+        assert_eq!(texts(&ime.smart_convert("bbubbu")), ["ꁮꀕ", "ꁮꁮ"]);
+        assert_eq!(ime.transliterate_text("bbubbu"), "ꁮꀕ");
+    }
+
+    #[test]
+    fn iteration_mark_in_mid_input() {
+        let mut ime = ime();
+        let candidates = ime.smart_convert("nuowsu");
+        assert_eq!(candidates[0].text, "ꆈꀕꌠ");
+        assert_eq!(candidates[0].pinyin(), "nuo-nuo-su");
+
+        ime.options.iteration_key = ';';
+        let candidates = ime.smart_convert("nuo;su");
+        assert_eq!(candidates[0].text, "ꆈꀕꌠ");
+        assert_eq!(candidates[0].pinyin(), "nuo-nuo-su");
+    }
+
+    #[test]
+    fn iteration_mark_is_not_offered_alone() {
+        let ime = ime();
+        for input in ["w", "'w"] {
+            assert!(ime.candidates(input).iter().all(|c| !c.text.contains(ITERATION_MARK)), "{}", input);
+            assert!(ime.smart_convert(input).iter().all(|c| !c.text.contains(ITERATION_MARK)), "{}", input);
+        }
+    }
+
+    #[test]
+    fn invalid_input_has_no_conversion() {
+        let ime = ime();
+        assert!(ime.smart_convert("b").is_empty());
+    }
+}
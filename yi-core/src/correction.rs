@@ -0,0 +1,223 @@
+use crate::ime::YiIME;
+use std::collections::HashMap;
+
+/// 默认的易混淆拼音片段：送气与否、清浊、鼻冠音和相近的韵母
+const DEFAULT_CONFUSIONS: [(&str, &str); 16] = [
+    ("hm", "m"),
+    ("hn", "n"),
+    ("hl", "l"),
+    ("nr", "n"),
+    ("ny", "n"),
+    ("ss", "s"),
+    ("zz", "z"),
+    ("jj", "j"),
+    ("rr", "r"),
+    ("bb", "b"),
+    ("dd", "d"),
+    ("gg", "g"),
+    ("ie", "i"),
+    ("uo", "o"),
+    ("ur", "u"),
+    ("yr", "y"),
+];
+
+/// QWERTY 键盘上每个字母相邻的按键
+const ADJACENT_KEYS: [&str; 26] = [
+    "qwsz", "vghn", "xdfv", "serfcx", "wsdr", "drtgvc", "ftyhbv", "gyujnb", "ujko", "huikmn",
+    "jiolm", "kop", "njk", "bhjm", "iklp", "ol", "wa", "edft", "awedxz", "rfgy", "yhji",
+    "cfgb", "qase", "zsdc", "tghu", "asx",
+];
+
+/// 各种编辑的代价：混淆和连按两次同一个键最常见，误触相邻按键次之，
+/// 凭空多打或漏打一个字母最少见
+const CONFUSION_COST: f32 = 0.5;
+const REPEATED_KEY_COST: f32 = 0.5;
+const ADJACENT_KEY_COST: f32 = 0.6;
+const TRANSPOSITION_COST: f32 = 0.8;
+const EXTRA_LETTER_COST: f32 = 1.0;
+const MISSING_LETTER_COST: f32 = 1.0;
+
+/// 纠错设置
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorrectionOptions {
+    /// 容易混淆的拼音片段，双向替换，例如 ("hm", "m")
+    pub confusions: Vec<(String, String)>,
+    /// 是否把相邻按键的误触视为可纠正的错误
+    pub adjacent_keys: bool,
+    /// 每个音节最多允许的编辑次数（多打、漏打、相邻字母颠倒、误触或混淆各算一次）
+    pub max_edits: usize,
+    /// 纠错得到的音节的置信度系数：编辑代价为 c 时乘以 penalty 的 c 次方
+    pub penalty: f32,
+    /// 每个输入片段最多给出的纠错结果数，编辑代价小的优先
+    pub max_corrections: usize,
+}
+
+impl Default for CorrectionOptions {
+    fn default() -> Self {
+        Self {
+            confusions: DEFAULT_CONFUSIONS
+                .iter()
+                .map(|(a, b)| (a.to_string(), b.to_string()))
+                .collect(),
+            adjacent_keys: true,
+            max_edits: 1,
+            penalty: 0.5,
+            max_corrections: 3,
+        }
+    }
+}
+
+impl CorrectionOptions {
+    /// 对 text 做一次编辑，把每个结果及其代价交给 visit（结果可能重复）
+    fn for_each_edit(&self, text: &str, mut visit: impl FnMut(String, f32)) {
+        let bytes = text.as_bytes();
+        // 把 [index, index + remove) 替换为 insert，结果不是合法 UTF-8 时丢弃
+        let mut edit = |index: usize, remove: usize, insert: &[u8], cost: f32| {
+            let mut edited = Vec::with_capacity(bytes.len() + insert.len());
+            edited.extend_from_slice(&bytes[..index]);
+            edited.extend_from_slice(insert);
+            edited.extend_from_slice(&bytes[index + remove..]);
+            if let Ok(edited) = String::from_utf8(edited) {
+                visit(edited, cost);
+            }
+        };
+
+        for (a, b) in &self.confusions {
+            for (from, to) in [(a, b), (b, a)] {
+                for (index, _) in text.match_indices(from.as_str()) {
+                    edit(index, from.len(), to.as_bytes(), CONFUSION_COST);
+                }
+            }
+        }
+
+        for i in 0..bytes.len() {
+            // 多打了一个字母：与前一个字母相同时多半是连按了两次
+            let repeated = i > 0 && bytes[i - 1] == bytes[i];
+            edit(i, 1, &[], if repeated { REPEATED_KEY_COST } else { EXTRA_LETTER_COST });
+
+            // 相邻字母颠倒
+            if i + 1 < bytes.len() {
+                edit(i, 2, &[bytes[i + 1], bytes[i]], TRANSPOSITION_COST);
+            }
+
+            // 误触相邻按键
+            if self.adjacent_keys && bytes[i].is_ascii_lowercase() {
+                for &neighbor in ADJACENT_KEYS[(bytes[i] - b'a') as usize].as_bytes() {
+                    edit(i, 1, &[neighbor], ADJACENT_KEY_COST);
+                }
+            }
+        }
+
+        // 漏打了一个字母
+        for i in 0..=bytes.len() {
+            for letter in b'a'..=b'z' {
+                edit(i, 0, &[letter], MISSING_LETTER_COST);
+            }
+        }
+    }
+}
+
+impl YiIME {
+    /// 与输入片段相差不超过 max_edits 次编辑的字典音节及其编辑代价
+    ///
+    /// 按代价从小到大排列（代价相同时按字典序），最多 max_corrections 个；
+    /// 片段本身不在结果中，纠错未开启时返回空列表。
+    pub fn corrections(&self, segment: &str) -> Vec<(String, f32)> {
+        let Some(options) = &self.options.correction else {
+            return Vec::new();
+        };
+        if !segment.is_ascii() {
+            return Vec::new();
+        }
+
+        let max_len = self.syllable_trie.max_len();
+        // 每个编辑结果的最小代价
+        let mut costs = HashMap::from([(segment.to_string(), 0.0)]);
+        let mut frontier = vec![(segment.to_string(), 0.0)];
+        let mut results: HashMap<String, f32> = HashMap::new();
+        for round in 1..=options.max_edits {
+            let remaining = options.max_edits - round;
+            let mut next = Vec::new();
+            for (text, cost) in &frontier {
+                options.for_each_edit(text, |edit, edit_cost| {
+                    // 剩下的编辑次数不足以缩短到音节长度
+                    if edit.is_empty() || edit.len() > max_len + remaining {
+                        return;
+                    }
+                    let total = cost + edit_cost;
+                    if costs.get(&edit).is_some_and(|&best| best <= total) {
+                        return;
+                    }
+                    costs.insert(edit.clone(), total);
+                    if self.syllable_set.contains(&edit) {
+                        results.insert(edit.clone(), total);
+                    }
+                    if remaining > 0 {
+                        next.push((edit, total));
+                    }
+                });
+            }
+            frontier = next;
+        }
+
+        let mut results: Vec<(String, f32)> = results.into_iter().collect();
+        results.sort_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        results.truncate(options.max_corrections);
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ime() -> YiIME {
+        let mut ime = YiIME::with_builtin_dictionaries().unwrap();
+        ime.options.correction = Some(CorrectionOptions::default());
+        ime
+    }
+
+    fn top(ime: &YiIME, input: &str, n: usize) -> Vec<String> {
+        ime.candidates(input).iter().take(n).map(|candidate| candidate.pinyin()).collect()
+    }
+
+    #[test]
+    fn likely_edits_cost_less() {
+        let ime = ime();
+        // 混淆 uo/o 比误触相邻按键 m/n 便宜，两者都比漏打一个字母便宜
+        let corrections = ime.corrections("mgo");
+        let pinyin: Vec<&str> = corrections.iter().map(|(pinyin, _)| pinyin.as_str()).collect();
+        assert_eq!(pinyin, ["mguo", "ngo", "go"]);
+        for pair in corrections.windows(2) {
+            assert!(pair[0].1 < pair[1].1);
+        }
+    }
+
+    #[test]
+    fn corrections_per_segment_are_capped() {
+        let mut ime = ime();
+        assert_eq!(ime.corrections("u").len(), 3);
+        ime.options.correction.as_mut().unwrap().max_corrections = 1;
+        assert_eq!(ime.corrections("u").len(), 1);
+    }
+
+    #[test]
+    fn realistic_typos_reach_the_top() {
+        let ime = ime();
+        // 连按了两次 u
+        assert!(top(&ime, "nuosuu", 3).contains(&"nuo-su".to_string()));
+        // u 和 o 颠倒
+        assert!(top(&ime, "nousu", 3).contains(&"nuo-su".to_string()));
+        // 多打了一个 y
+        assert!(top(&ime, "hxiyt", 3).contains(&"hxit".to_string()));
+    }
+
+    #[test]
+    fn exact_input_is_not_corrected() {
+        let ime = ime();
+        assert!(ime.corrections("nuo").iter().all(|(pinyin, _)| pinyin != "nuo"));
+        let mut ime = ime;
+        ime.options.correction = None;
+        assert!(ime.corrections("nuosuu").is_empty());
+    }
+}
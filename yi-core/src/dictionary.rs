@@ -0,0 +1,518 @@
+use serde::de::{Deserializer, MapAccess, Visitor};
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// 当前支持的最高字典格式版本
+///
+/// - 版本 1：`{"ꀊ": "a"}` 形式的扁平映射（没有 version 字段时按此处理）
+/// - 版本 2：`{"version": 2, "entries": {...}}`，条目可以是字符串、字符串数组或对象
+/// - 版本 3：音节字典可以附带 `"phrases": {"ꆈꌠ": 1200}` 形式的词语频率
+pub const DICTIONARY_SCHEMA_VERSION: u64 = 3;
+
+/// 允许使用 phrases 字段的最低版本
+const PHRASES_SCHEMA_VERSION: u64 = 3;
+
+/// 字典条目：一个彝文字符（或部首）的读音及元数据
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct DictEntry {
+    /// 拼音读音，第一个为主读音
+    pub readings: Vec<String>,
+    /// 使用频率（语料中的出现次数）
+    pub frequency: Option<u64>,
+    /// 是否属于规范彝文
+    pub standard: bool,
+    /// 释义
+    pub gloss: Option<String>,
+    /// Unicode 字符名称，例如 "YI SYLLABLE A"
+    pub unicode_name: Option<String>,
+}
+
+impl Default for DictEntry {
+    fn default() -> Self {
+        Self {
+            readings: Vec::new(),
+            frequency: None,
+            standard: true,
+            gloss: None,
+            unicode_name: None,
+        }
+    }
+}
+
+impl DictEntry {
+    /// 只有读音的条目
+    pub fn with_readings(readings: Vec<String>) -> Self {
+        Self {
+            readings,
+            ..Self::default()
+        }
+    }
+}
+
+/// 条目在 JSON 中允许的三种写法
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawEntry {
+    Reading(String),
+    Readings(Vec<String>),
+    Detailed(DictEntry),
+}
+
+impl From<RawEntry> for DictEntry {
+    fn from(raw: RawEntry) -> Self {
+        match raw {
+            RawEntry::Reading(reading) => DictEntry::with_readings(vec![reading]),
+            RawEntry::Readings(readings) => DictEntry::with_readings(readings),
+            RawEntry::Detailed(entry) => entry,
+        }
+    }
+}
+
+/// 字典加载错误
+#[derive(Debug)]
+pub enum DictError {
+    /// 读取文件失败
+    Io(std::io::Error),
+    /// JSON 语法错误
+    Json { line: usize, column: usize, message: String },
+    /// 顶层结构不符合字典格式
+    InvalidSchema(String),
+    /// 不支持的格式版本
+    UnknownSchemaVersion(String),
+    /// 条目值既不是字符串、字符串数组，也不是合法的对象
+    InvalidEntry { key: String, message: String },
+    /// 同一字符出现多次
+    DuplicateCharacter(String),
+    /// 同一拼音对应了多个字符
+    DuplicatePinyin { pinyin: String, existing: String, duplicate: String },
+    /// 条目没有读音，或读音为空字符串
+    EmptyReadings(String),
+    /// 键不是该字典应有的彝文字符
+    NonYiCodepoint(String),
+}
+
+impl std::fmt::Display for DictError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DictError::Io(e) => write!(f, "读取字典失败: {}", e),
+            DictError::Json { line, column, message } => {
+                write!(f, "字典 JSON 语法错误（第 {} 行第 {} 列）: {}", line, column, message)
+            }
+            DictError::InvalidSchema(message) => write!(f, "字典格式错误: {}", message),
+            DictError::UnknownSchemaVersion(version) => write!(f, "不支持的字典格式版本: {}", version),
+            DictError::InvalidEntry { key, message } => write!(f, "条目 {} 无效: {}", key, message),
+            DictError::DuplicateCharacter(key) => write!(f, "字符 {} 重复出现", key),
+            DictError::DuplicatePinyin { pinyin, existing, duplicate } => {
+                write!(f, "拼音 {} 同时对应 {} 和 {}", pinyin, existing, duplicate)
+            }
+            DictError::EmptyReadings(key) => write!(f, "条目 {} 没有读音", key),
+            DictError::NonYiCodepoint(key) => write!(f, "{:?} 不是彝文字符", key),
+        }
+    }
+}
+
+impl std::error::Error for DictError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DictError::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for DictError {
+    fn from(e: std::io::Error) -> Self {
+        DictError::Io(e)
+    }
+}
+
+impl From<serde_json::Error> for DictError {
+    fn from(e: serde_json::Error) -> Self {
+        DictError::Json {
+            line: e.line(),
+            column: e.column(),
+            message: e.to_string(),
+        }
+    }
+}
+
+/// 加载模式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LoadMode {
+    /// 遇到任何有问题的条目都立即失败
+    #[default]
+    Strict,
+    /// 跳过无法加载的条目，并把所有问题记录为警告
+    Lenient,
+}
+
+/// 加载结果报告
+#[derive(Debug, Default)]
+pub struct LoadReport {
+    /// 成功加载的条目数
+    pub loaded: usize,
+    /// 宽松模式下被跳过或有问题的条目，以及任何模式下都只需提示的情况（如部首共用读音）
+    pub warnings: Vec<DictError>,
+}
+
+impl LoadReport {
+    /// 严格模式下返回错误，宽松模式下记录为警告
+    pub(crate) fn check(&mut self, mode: LoadMode, error: DictError) -> Result<(), DictError> {
+        match mode {
+            LoadMode::Strict => Err(error),
+            LoadMode::Lenient => {
+                self.warnings.push(error);
+                Ok(())
+            }
+        }
+    }
+
+    /// 记录不影响加载的问题，任何模式下都不返回错误
+    pub(crate) fn warn(&mut self, error: DictError) {
+        self.warnings.push(error);
+    }
+}
+
+/// 字典种类，决定键应位于哪个 Unicode 区块
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DictionaryKind {
+    /// 彝文音节（U+A000–U+A48F）
+    Syllable,
+    /// 彝文部首（U+A490–U+A4CF）
+    Radical,
+}
+
+impl DictionaryKind {
+    fn accepts(&self, key: &str) -> bool {
+        let mut chars = key.chars();
+        let (Some(ch), None) = (chars.next(), chars.next()) else {
+            return false;
+        };
+        self.accepts_char(ch)
+    }
+
+    fn accepts_char(&self, ch: char) -> bool {
+        match self {
+            DictionaryKind::Syllable => ('\u{A000}'..='\u{A48F}').contains(&ch),
+            DictionaryKind::Radical => ('\u{A490}'..='\u{A4CF}').contains(&ch),
+        }
+    }
+}
+
+/// 解析后的字典内容
+#[derive(Debug, Clone, Default)]
+pub struct ParsedDictionary {
+    /// 按出现顺序排列的 (字符, 条目)
+    pub entries: Vec<(String, DictEntry)>,
+    /// 按出现顺序排列的 (词语, 频率)
+    pub phrases: Vec<(String, u64)>,
+}
+
+/// 解析字典 JSON
+///
+/// JSON 语法错误、顶层结构错误和未知版本总是直接失败；
+/// 条目级别的问题按加载模式处理，宽松模式下会跳过该条目。
+pub fn parse_dictionary(
+    json_content: &str,
+    kind: DictionaryKind,
+    mode: LoadMode,
+    report: &mut LoadReport,
+) -> Result<ParsedDictionary, DictError> {
+    let mut raw: RawDictionary = serde_json::from_str(json_content)?;
+    let raw_phrases = raw.phrases.take();
+    let version = raw.version.as_ref().and_then(Value::as_u64);
+    let raw_entries = raw.into_entries()?;
+    let raw_phrases = phrases_for_version(raw_phrases, version, kind)?;
+
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for (key, value) in raw_entries {
+        if !seen.insert(key.clone()) {
+            report.check(mode, DictError::DuplicateCharacter(key))?;
+            continue;
+        }
+
+        if !kind.accepts(&key) {
+            report.check(mode, DictError::NonYiCodepoint(key))?;
+            continue;
+        }
+
+        let mut entry: DictEntry = match serde_json::from_value::<RawEntry>(value) {
+            Ok(raw) => raw.into(),
+            Err(_) => {
+                let message = "条目必须是字符串、字符串数组或包含 readings 的对象".to_string();
+                report.check(mode, DictError::InvalidEntry { key, message })?;
+                continue;
+            }
+        };
+
+        if entry.readings.is_empty() || entry.readings.iter().any(|r| r.trim().is_empty()) {
+            report.check(mode, DictError::EmptyReadings(key))?;
+            continue;
+        }
+
+        // 同一条目中重复的读音只保留第一个
+        let mut readings: Vec<String> = Vec::with_capacity(entry.readings.len());
+        for reading in std::mem::take(&mut entry.readings) {
+            if readings.contains(&reading) {
+                report.check(mode, DictError::DuplicatePinyin {
+                    pinyin: reading,
+                    existing: key.clone(),
+                    duplicate: key.clone(),
+                })?;
+            } else {
+                readings.push(reading);
+            }
+        }
+        entry.readings = readings;
+
+        entries.push((key, entry));
+    }
+
+    let mut phrases = Vec::new();
+    let mut seen = HashSet::new();
+    for (key, value) in raw_phrases {
+        if !seen.insert(key.clone()) {
+            report.check(mode, DictError::InvalidEntry { key, message: "词语重复出现".to_string() })?;
+            continue;
+        }
+        if key.chars().count() < 2 || !key.chars().all(|c| kind.accepts_char(c)) {
+            report.check(mode, DictError::NonYiCodepoint(key))?;
+            continue;
+        }
+        let Some(frequency) = value.as_u64() else {
+            let message = format!("词语频率必须是非负整数，实际为 {}", json_type_name(&value));
+            report.check(mode, DictError::InvalidEntry { key, message })?;
+            continue;
+        };
+        phrases.push((key, frequency));
+    }
+
+    Ok(ParsedDictionary { entries, phrases })
+}
+
+/// 保留重复键的 JSON 对象
+struct OrderedMap(Vec<(String, Value)>);
+
+impl<'de> Deserialize<'de> for OrderedMap {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OrderedMapVisitor;
+
+        impl<'de> Visitor<'de> for OrderedMapVisitor {
+            type Value = OrderedMap;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a JSON object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<OrderedMap, A::Error> {
+                let mut pairs = Vec::new();
+                while let Some((key, value)) = map.next_entry::<String, Value>()? {
+                    pairs.push((key, value));
+                }
+                Ok(OrderedMap(pairs))
+            }
+        }
+
+        deserializer.deserialize_map(OrderedMapVisitor)
+    }
+}
+
+/// entries 字段：正常情况下是对象，否则保留原值以便报告错误
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawEntries {
+    Map(OrderedMap),
+    Other(Value),
+}
+
+/// 字典文件的顶层结构
+struct RawDictionary {
+    version: Option<Value>,
+    entries: Option<RawEntries>,
+    phrases: Option<RawEntries>,
+    /// 旧格式中直接位于顶层的条目
+    pairs: Vec<(String, Value)>,
+}
+
+impl<'de> Deserialize<'de> for RawDictionary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawDictionaryVisitor;
+
+        impl<'de> Visitor<'de> for RawDictionaryVisitor {
+            type Value = RawDictionary;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a dictionary object")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawDictionary, A::Error> {
+                let mut raw = RawDictionary {
+                    version: None,
+                    entries: None,
+                    phrases: None,
+                    pairs: Vec::new(),
+                };
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        "version" => raw.version = Some(map.next_value()?),
+                        "entries" => raw.entries = Some(map.next_value()?),
+                        "phrases" => raw.phrases = Some(map.next_value()?),
+                        _ => raw.pairs.push((key, map.next_value()?)),
+                    }
+                }
+                Ok(raw)
+            }
+        }
+
+        deserializer.deserialize_map(RawDictionaryVisitor)
+    }
+}
+
+/// 取出词语频率：只有版本 3 起的音节字典可以包含 phrases
+fn phrases_for_version(
+    phrases: Option<RawEntries>,
+    version: Option<u64>,
+    kind: DictionaryKind,
+) -> Result<Vec<(String, Value)>, DictError> {
+    let Some(phrases) = phrases else {
+        return Ok(Vec::new());
+    };
+    if kind != DictionaryKind::Syllable {
+        return Err(DictError::InvalidSchema("部首字典不能包含 phrases".to_string()));
+    }
+    if version.unwrap_or(1) < PHRASES_SCHEMA_VERSION {
+        return Err(DictError::InvalidSchema(format!(
+            "phrases 字段需要格式版本 {} 或以上",
+            PHRASES_SCHEMA_VERSION
+        )));
+    }
+    match phrases {
+        RawEntries::Map(OrderedMap(phrases)) => Ok(phrases),
+        RawEntries::Other(value) => Err(DictError::InvalidSchema(
+            format!("phrases 必须是对象，实际为 {}", json_type_name(&value))
+        )),
+    }
+}
+
+impl RawDictionary {
+    /// 根据格式版本取出条目
+    fn into_entries(self) -> Result<Vec<(String, Value)>, DictError> {
+        // 没有 version 字段的旧格式：整个对象就是条目映射
+        let Some(version) = self.version else {
+            if self.entries.is_some() {
+                return Err(DictError::InvalidSchema("entries 字段需要同时提供 version".to_string()));
+            }
+            return Ok(self.pairs);
+        };
+
+        match version.as_u64() {
+            Some(1..=DICTIONARY_SCHEMA_VERSION) => {}
+            _ => return Err(DictError::UnknownSchemaVersion(version.to_string())),
+        }
+
+        match self.entries {
+            Some(RawEntries::Map(OrderedMap(entries))) => Ok(entries),
+            Some(RawEntries::Other(value)) => Err(DictError::InvalidSchema(
+                format!("entries 必须是对象，实际为 {}", json_type_name(&value))
+            )),
+            None => Err(DictError::InvalidSchema("缺少 entries 对象".to_string())),
+        }
+    }
+}
+
+fn json_type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "布尔值",
+        Value::Number(_) => "数字",
+        Value::String(_) => "字符串",
+        Value::Array(_) => "数组",
+        Value::Object(_) => "对象",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ime::YiIME;
+
+    #[test]
+    fn repeated_readings_in_one_entry() {
+        let content = r#"{"version": 2, "entries": {"ꀀ": ["it", "it", "ix"]}}"#;
+
+        let mut ime = YiIME::new();
+        let error = ime.load_dictionary_with_mode(content, LoadMode::Strict).unwrap_err();
+        assert!(matches!(error, DictError::DuplicatePinyin { pinyin, .. } if pinyin == "it"));
+
+        let mut ime = YiIME::new();
+        let report = ime.load_dictionary_with_mode(content, LoadMode::Lenient).unwrap();
+        assert_eq!(report.loaded, 1);
+        assert_eq!(report.warnings.len(), 1);
+        assert_eq!(ime.dictionary["ꀀ"], ["it", "ix"]);
+        assert_eq!(ime.query_by_pinyin("it").len(), 1);
+    }
+
+    #[test]
+    fn lenient_mode_skips_bad_entries() {
+        let content = r#"{"ꀀ": "it", "a": "a", "ꀁ": "", "ꀂ": 1}"#;
+        let mut ime = YiIME::new();
+        assert!(ime.load_dictionary_with_mode(content, LoadMode::Strict).is_err());
+
+        let mut ime = YiIME::new();
+        let report = ime.load_dictionary_with_mode(content, LoadMode::Lenient).unwrap();
+        assert_eq!(report.loaded, 1);
+        assert_eq!(report.warnings.len(), 3);
+    }
+
+    #[test]
+    fn json_errors_report_position() {
+        let content = "{\n  \"ꀀ\": \"it\",\n  \"ꀁ\" \"ix\"\n}";
+        let error = YiIME::new().load_dictionary_from_str(content).unwrap_err();
+        // 列号按字节计算，ꀁ 占三个字节
+        assert!(matches!(error, DictError::Json { line: 3, column: 9, .. }), "{:?}", error);
+    }
+
+    #[test]
+    fn unknown_schema_versions() {
+        for version in ["0", "99", "\"2\"", "1.5"] {
+            let content = format!(r#"{{"version": {}, "entries": {{"ꀀ": "it"}}}}"#, version);
+            let mut report = LoadReport::default();
+            let error = parse_dictionary(&content, DictionaryKind::Syllable, LoadMode::Lenient, &mut report).unwrap_err();
+            assert!(matches!(&error, DictError::UnknownSchemaVersion(v) if *v == version), "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn failed_strict_load_leaves_ime_unchanged() {
+        let mut ime = YiIME::new();
+        ime.load_dictionary_from_str(r#"{"ꀀ": "it"}"#).unwrap();
+
+        // 第一个条目本身没有问题，第二个与已加载的读音冲突
+        let error = ime.load_dictionary_from_str(r#"{"ꀁ": "ix", "ꀂ": "it"}"#).unwrap_err();
+        assert!(matches!(error, DictError::DuplicatePinyin { pinyin, .. } if pinyin == "it"));
+        // 文件内部的读音冲突同样在修改索引之前发现
+        assert!(ime.load_dictionary_from_str(r#"{"ꀁ": "ix", "ꀂ": "ix"}"#).is_err());
+        assert_eq!(ime.dictionary.len(), 1);
+        assert!(!ime.syllable_set.contains("ix"));
+        assert!(!ime.syllable_trie.contains("ix"));
+        assert!(!ime.pinyin_index.contains_key("ix"));
+        assert!(ime.entry("ꀁ").is_none());
+
+        ime.load_radical_dictionary_from_str(r#"{"꒐": "ggop"}"#).unwrap();
+        assert!(ime.load_radical_dictionary_from_str(r#"{"꒑": "qot", "꒐": "ggop"}"#).is_err());
+        assert_eq!(ime.radical_dictionary.len(), 1);
+        assert!(!ime.radical_trie.contains("qot"));
+
+        ime.load_dictionary_from_str(r#"{"ꀁ": "ix"}"#).unwrap();
+        let lexicon = r#"{"version": 1, "words": [
+            {"text": "ꀀꀁ", "pinyin": "it ix", "frequency": 10},
+            {"text": "ꀁꀀ", "pinyin": "ix ip"}
+        ]}"#;
+        assert!(ime.load_lexicon_from_str(lexicon).is_err());
+        assert!(ime.lexicon.is_empty());
+        assert!(ime.phrase_weight("ꀀꀁ").is_none());
+    }
+}
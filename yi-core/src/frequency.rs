@@ -0,0 +1,150 @@
+use crate::ime::YiIME;
+use std::collections::HashMap;
+
+/// 随字典加载的使用频率
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyTable {
+    /// 词语 -> 频率
+    pub phrases: HashMap<String, u64>,
+    /// 词语首字 -> 以该字开头的词语，按加载顺序排列
+    pub phrase_index: HashMap<char, Vec<String>>,
+    /// 字符频率的最大值
+    pub max_char: u64,
+    /// 词语频率的最大值
+    pub max_phrase: u64,
+}
+
+impl FrequencyTable {
+    /// 记录一个字符的频率
+    pub fn add_char(&mut self, frequency: u64) {
+        self.max_char = self.max_char.max(frequency);
+    }
+
+    /// 记录一个词语的频率，重复加载时以后加载的为准
+    pub fn add_phrase(&mut self, phrase: &str, frequency: u64) {
+        let Some(first) = phrase.chars().next() else {
+            return;
+        };
+        if self.phrases.insert(phrase.to_string(), frequency).is_none() {
+            self.phrase_index.entry(first).or_default().push(phrase.to_string());
+        }
+        self.max_phrase = self.max_phrase.max(frequency);
+    }
+}
+
+/// 把频率换算为 (0, 1] 之间的权重：按对数缩放，没有频率的按 0 计算；
+/// 字典中完全没有频率时所有权重都是 1，排序与不使用频率时相同
+fn frequency_weight(frequency: u64, max: u64) -> f32 {
+    let scale = |f: u64| 1.0 + (f as f64).ln_1p();
+    (scale(frequency.min(max)) / scale(max)) as f32
+}
+
+impl YiIME {
+    /// 字符（音节或部首）的频率权重
+    pub fn char_weight(&self, yi_char: &str) -> f32 {
+        let frequency = self.entries.get(yi_char).and_then(|entry| entry.frequency).unwrap_or(0);
+        frequency_weight(frequency, self.frequencies.max_char)
+    }
+
+    /// 词语的频率权重，不在词语频率表中时为 None
+    pub fn phrase_weight(&self, phrase: &str) -> Option<f32> {
+        let frequency = *self.frequencies.phrases.get(phrase)?;
+        Some(frequency_weight(frequency, self.frequencies.max_phrase))
+    }
+
+    /// 能由各组候选字逐字组成的已知词语
+    pub fn phrases_for(&self, yi_chars: &[Vec<String>]) -> Vec<String> {
+        let Some(first_group) = yi_chars.first() else {
+            return Vec::new();
+        };
+        let mut phrases = Vec::new();
+        for first in first_group {
+            let Some(first) = first.chars().next() else {
+                continue;
+            };
+            for phrase in self.frequencies.phrase_index.get(&first).into_iter().flatten() {
+                let chars: Vec<char> = phrase.chars().collect();
+                let matches = chars.len() == yi_chars.len()
+                    && chars.iter().zip(yi_chars).all(|(ch, group)| {
+                        group.iter().any(|yi_char| yi_char.chars().eq(std::iter::once(*ch)))
+                    });
+                if matches {
+                    phrases.push(phrase.clone());
+                }
+            }
+        }
+        phrases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 同一韵母的四个声调和 a、ap，ꀃ 的频率最高，ꀀ 和 ꀁ 的频率相同
+    const DICTIONARY: &str = r#"{"version": 3,
+        "entries": {
+            "ꀀ": {"readings": ["it"], "frequency": 10},
+            "ꀁ": {"readings": ["ix"], "frequency": 10},
+            "ꀂ": {"readings": ["i"], "frequency": 10},
+            "ꀃ": {"readings": ["ip"], "frequency": 1000},
+            "ꀊ": {"readings": ["a"], "frequency": 100},
+            "ꀋ": {"readings": ["ap"], "frequency": 100}
+        },
+        "phrases": {"ꀁꀋ": 5000}}"#;
+
+    /// 相同的字，没有频率和词语
+    const WITHOUT_FREQUENCIES: &str = r#"{"ꀀ": "it", "ꀁ": "ix", "ꀂ": "i", "ꀃ": "ip", "ꀊ": "a", "ꀋ": "ap"}"#;
+
+    fn toneless_ime(dictionary: &str) -> YiIME {
+        let mut ime = YiIME::new();
+        ime.load_dictionary_from_str(dictionary).unwrap();
+        ime.options.toneless = true;
+        ime
+    }
+
+    fn pinyin(ime: &YiIME, input: &str) -> Vec<String> {
+        ime.candidates(input).iter().map(|candidate| candidate.pinyin()).collect()
+    }
+
+    #[test]
+    fn weights_follow_frequencies() {
+        let ime = toneless_ime(DICTIONARY);
+        assert_eq!(ime.char_weight("ꀃ"), 1.0);
+        assert!(ime.char_weight("ꀀ") < ime.char_weight("ꀊ"));
+        assert_eq!(ime.char_weight("ꀀ"), ime.char_weight("ꀁ"));
+        assert_eq!(ime.phrase_weight("ꀁꀋ"), Some(1.0));
+        assert_eq!(ime.phrase_weight("ꀀꀋ"), None);
+
+        // 没有频率时所有权重都是 1
+        let plain = toneless_ime(WITHOUT_FREQUENCIES);
+        assert_eq!(plain.char_weight("ꀀ"), 1.0);
+        assert_eq!(plain.char_weight("ꀃ"), 1.0);
+    }
+
+    #[test]
+    fn frequent_character_moves_up() {
+        assert_eq!(pinyin(&toneless_ime(WITHOUT_FREQUENCIES), "i"), ["i", "it", "ix", "ip"]);
+        assert_eq!(pinyin(&toneless_ime(DICTIONARY), "i"), ["ip", "i", "it", "ix"]);
+    }
+
+    #[test]
+    fn frequent_phrase_moves_up() {
+        let rank = |ime: &YiIME| pinyin(ime, "ia").iter().position(|p| p == "ix-ap").unwrap();
+        let plain = toneless_ime(WITHOUT_FREQUENCIES);
+        let ime = toneless_ime(DICTIONARY);
+        assert!(rank(&ime) < rank(&plain));
+        assert_eq!(rank(&ime), 2);
+    }
+
+    #[test]
+    fn equal_weights_keep_a_deterministic_order() {
+        // 每个实例的哈希表顺序不同，排序结果必须相同
+        let expected = pinyin(&toneless_ime(DICTIONARY), "ia");
+        for _ in 0..5 {
+            let ime = toneless_ime(DICTIONARY);
+            assert_eq!(pinyin(&ime, "ia"), expected);
+            assert_eq!(pinyin(&ime, "i"), ["ip", "i", "it", "ix"]);
+        }
+    }
+}
@@ -0,0 +1,388 @@
+use crate::candidates::{Candidate, CandidateKind, CandidateSource};
+use crate::dictionary::{parse_dictionary, DictEntry, DictError, DictionaryKind, LoadMode, LoadReport};
+use crate::frequency::FrequencyTable;
+use crate::language_model::LanguageModel;
+use crate::lexicon::Lexicon;
+use crate::options::ImeOptions;
+use crate::syllable::{Syllable, Tone};
+use crate::toneless::TONELESS_WEIGHT;
+use crate::trie::PinyinTrie;
+use crate::user_dict::UserDictionary;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::sync::Arc;
+
+/// 内置的彝文音节字典
+pub const YI_SYLLABLE_DICT: &str = include_str!("../../assets/彝文音节字典.json");
+/// 内置的彝文部首字典
+pub const YI_RADICAL_DICT: &str = include_str!("../../assets/彝文部首字典.json");
+/// 内置的多音节词库
+pub const YI_LEXICON: &str = include_str!("../../assets/彝文词库.json");
+
+/// 替字符号（重复前一音节），输入时用 w 表示
+pub const ITERATION_MARK: char = 'ꀕ';
+
+/// 部首候选项的分数
+pub(crate) const RADICAL_SCORE: f32 = 0.9;
+
+/// 彝文输入法核心结构
+#[derive(Debug, Clone)]
+pub struct YiIME {
+    /// 字典：彝文字符 -> 拼音编码列表
+    pub dictionary: HashMap<String, Vec<String>>,
+    /// 反向索引：拼音编码 -> 彝文字符列表
+    pub pinyin_index: HashMap<String, Vec<String>>,
+    /// 所有可能的音节集合，用于分词
+    pub syllable_set: HashSet<String>,
+    /// 部首字典：部首字符 -> 拼音编码
+    pub radical_dictionary: HashMap<String, String>,
+    /// 部首反向索引：拼音编码 -> 部首字符列表，按码位（即彝文部首区块中的顺序）排列
+    pub radical_pinyin_index: HashMap<String, Vec<String>>,
+    /// 字典条目：彝文字符或部首 -> 读音、频率、释义等元数据
+    pub entries: HashMap<String, DictEntry>,
+    /// 音节前缀树，用于前缀查询和分词
+    pub syllable_trie: PinyinTrie,
+    /// 部首拼音前缀树
+    pub radical_trie: PinyinTrie,
+    /// 无声调索引：去掉声调标记的拼音 -> 字典中的各声调拼音
+    pub toneless_index: HashMap<String, Vec<String>>,
+    /// 去掉声调标记的拼音前缀树，用于无声调输入时的分词
+    pub toneless_trie: PinyinTrie,
+    /// 字符和词语的使用频率
+    pub frequencies: FrequencyTable,
+    /// 多音节词库
+    pub lexicon: Lexicon,
+    /// 用户词库
+    pub user_dictionary: UserDictionary,
+    /// 分词时使用的语言模型，None 表示按音节长度估计置信度
+    pub language_model: Option<Arc<dyn LanguageModel>>,
+    /// 可选行为
+    pub options: ImeOptions,
+}
+
+impl YiIME {
+    /// 创建新的输入法实例
+    pub fn new() -> Self {
+        YiIME {
+            dictionary: HashMap::new(),
+            pinyin_index: HashMap::new(),
+            syllable_set: HashSet::new(),
+            radical_dictionary: HashMap::new(),
+            radical_pinyin_index: HashMap::new(),
+            entries: HashMap::new(),
+            syllable_trie: PinyinTrie::new(),
+            radical_trie: PinyinTrie::new(),
+            toneless_index: HashMap::new(),
+            toneless_trie: PinyinTrie::new(),
+            frequencies: FrequencyTable::default(),
+            lexicon: Lexicon::default(),
+            user_dictionary: UserDictionary::default(),
+            language_model: None,
+            options: ImeOptions::default(),
+        }
+    }
+
+    /// 创建加载了内置音节字典、部首字典和词库的输入法实例
+    pub fn with_builtin_dictionaries() -> Result<Self, DictError> {
+        let mut ime = Self::new();
+        ime.load_dictionary_from_str(YI_SYLLABLE_DICT)?;
+        ime.load_radical_dictionary_from_str(YI_RADICAL_DICT)?;
+        ime.load_lexicon_from_str(YI_LEXICON)?;
+        Ok(ime)
+    }
+
+    /// 从JSON文件加载字典
+    pub fn load_dictionary(&mut self, file_path: &str) -> Result<(), DictError> {
+        let content = fs::read_to_string(file_path)?;
+        self.load_dictionary_from_str(&content)
+    }
+
+    /// 从JSON文件加载部首字典
+    pub fn load_radical_dictionary(&mut self, file_path: &str) -> Result<(), DictError> {
+        let content = fs::read_to_string(file_path)?;
+        self.load_radical_dictionary_from_str(&content)
+    }
+
+    /// 从嵌入的JSON字符串加载字典（严格模式）
+    ///
+    /// 支持旧的扁平格式和带版本号的格式，条目可以是字符串、字符串数组或对象，
+    /// 参见 [`crate::dictionary`]。
+    pub fn load_dictionary_from_str(&mut self, json_content: &str) -> Result<(), DictError> {
+        self.load_dictionary_with_mode(json_content, LoadMode::Strict).map(|_| ())
+    }
+
+    /// 从嵌入的JSON字符串加载部首字典（严格模式，格式与音节字典相同）
+    pub fn load_radical_dictionary_from_str(&mut self, json_content: &str) -> Result<(), DictError> {
+        self.load_radical_dictionary_with_mode(json_content, LoadMode::Strict).map(|_| ())
+    }
+
+    /// 按指定模式加载字典，宽松模式下返回的报告中包含被跳过的条目
+    ///
+    /// 整个文件解析并检查完毕后才修改索引，严格模式下失败时输入法保持原状。
+    pub fn load_dictionary_with_mode(&mut self, json_content: &str, mode: LoadMode) -> Result<LoadReport, DictError> {
+        let mut report = LoadReport::default();
+        let parsed = parse_dictionary(json_content, DictionaryKind::Syllable, mode, &mut report)?;
+
+        // 本文件中已接受的拼音 -> 第一个字符，与已加载的索引一起检查重复
+        let mut accepted_pinyin: HashMap<&str, &str> = HashMap::new();
+        let mut accepted = Vec::with_capacity(parsed.entries.len());
+        for (yi_char, entry) in &parsed.entries {
+            if self.dictionary.contains_key(yi_char) {
+                report.check(mode, DictError::DuplicateCharacter(yi_char.clone()))?;
+                continue;
+            }
+
+            for pinyin in &entry.readings {
+                let existing = self
+                    .pinyin_index
+                    .get(pinyin)
+                    .and_then(|chars| chars.first())
+                    .map(String::as_str)
+                    .or_else(|| accepted_pinyin.get(pinyin.as_str()).copied());
+                if let Some(existing) = existing {
+                    report.check(mode, DictError::DuplicatePinyin {
+                        pinyin: pinyin.clone(),
+                        existing: existing.to_string(),
+                        duplicate: yi_char.clone(),
+                    })?;
+                }
+            }
+            for pinyin in &entry.readings {
+                accepted_pinyin.entry(pinyin).or_insert(yi_char);
+            }
+            accepted.push((yi_char, entry));
+        }
+
+        for (yi_char, entry) in accepted {
+            for pinyin in &entry.readings {
+                // 建立反向索引
+                self.pinyin_index
+                    .entry(pinyin.clone())
+                    .or_default()
+                    .push(yi_char.clone());
+
+                // 添加到音节集合和前缀树
+                self.syllable_trie.insert(pinyin);
+                self.syllable_set.insert(pinyin.clone());
+                self.index_toneless(pinyin);
+            }
+
+            self.dictionary.insert(yi_char.clone(), entry.readings.clone());
+            self.frequencies.add_char(entry.frequency.unwrap_or(0));
+            self.entries.insert(yi_char.clone(), entry.clone());
+            report.loaded += 1;
+        }
+
+        for (phrase, frequency) in &parsed.phrases {
+            self.frequencies.add_phrase(phrase, *frequency);
+        }
+
+        Ok(report)
+    }
+
+    /// 按指定模式加载部首字典，同样在检查完整个文件后才修改索引
+    pub fn load_radical_dictionary_with_mode(&mut self, json_content: &str, mode: LoadMode) -> Result<LoadReport, DictError> {
+        let mut report = LoadReport::default();
+        let parsed = parse_dictionary(json_content, DictionaryKind::Radical, mode, &mut report)?;
+
+        let mut accepted = Vec::with_capacity(parsed.entries.len());
+        for (radical_char, entry) in parsed.entries {
+            if self.radical_dictionary.contains_key(&radical_char) {
+                report.check(mode, DictError::DuplicateCharacter(radical_char))?;
+                continue;
+            }
+            accepted.push((radical_char, entry));
+        }
+
+        for (radical_char, entry) in accepted {
+            self.radical_dictionary.insert(radical_char.clone(), entry.readings[0].clone());
+            for pinyin in &entry.readings {
+                self.radical_trie.insert(pinyin);
+                let radicals = self.radical_pinyin_index.entry(pinyin.clone()).or_default();
+                // 多个部首可以共用一个读音，都保留下来，只在报告中提示
+                if let Some(existing) = radicals.first() {
+                    report.warn(DictError::DuplicatePinyin {
+                        pinyin: pinyin.clone(),
+                        existing: existing.clone(),
+                        duplicate: radical_char.clone(),
+                    });
+                }
+                radicals.push(radical_char.clone());
+                radicals.sort();
+            }
+            self.frequencies.add_char(entry.frequency.unwrap_or(0));
+            self.entries.insert(radical_char, entry);
+            report.loaded += 1;
+        }
+
+        Ok(report)
+    }
+
+    /// 查询字符（音节或部首）的字典条目
+    pub fn entry(&self, yi_char: &str) -> Option<&DictEntry> {
+        self.entries.get(yi_char)
+    }
+
+    /// 是否存在以 prefix 开头的音节或部首拼音
+    pub fn is_pinyin_prefix(&self, prefix: &str) -> bool {
+        self.syllable_trie.is_prefix(prefix) || self.radical_trie.is_prefix(prefix)
+    }
+
+    /// 检查字符是否为歧义字符
+    ///
+    /// t、x、p 既可以是声调标记也可以是下一个音节的声母，
+    /// r、y 既可以是韵母（ur、y、yr）的一部分也可以是声母。
+    pub fn is_ambiguous_char(c: char) -> bool {
+        Tone::from_marker(c).is_some() || matches!(c, 'r' | 'y')
+    }
+
+    /// 将字典中的拼音解析为声母、韵母和声调
+    pub fn parse_syllable(&self, pinyin: &str) -> Option<Syllable> {
+        if !self.syllable_set.contains(pinyin) && !self.radical_pinyin_index.contains_key(pinyin) {
+            return None;
+        }
+        Syllable::parse(pinyin).ok()
+    }
+
+    /// 根据拼音编码查询彝文字符（包含部首）
+    ///
+    /// 音节按使用频率排序；无声调输入时同时返回其他声调的字，
+    /// 声调完全匹配的字分数更高。部首排在最后。
+    pub fn query_by_pinyin(&self, pinyin: &str) -> Vec<Candidate> {
+        let mut results: Vec<Candidate> = Vec::new();
+        for reading in self.segment_readings(pinyin) {
+            let tone_weight = if reading == pinyin { 1.0 } else { TONELESS_WEIGHT };
+            for yi_char in self.pinyin_index.get(&reading).into_iter().flatten() {
+                results.push(Candidate::new(
+                    yi_char.clone(),
+                    vec![reading.clone()],
+                    CandidateKind::Syllable,
+                    tone_weight * self.char_weight(yi_char),
+                    CandidateSource::Dictionary,
+                ));
+            }
+        }
+        // 按分数排序，分数相同时保持字典中的顺序
+        results.sort_by(|a, b| b.score.total_cmp(&a.score));
+        
+        // 该拼音对应的部首按区块顺序添加到结果中，分数相同以免之后按分数排序时打乱顺序
+        for radical in self.get_radical_candidates(pinyin) {
+            results.push(Candidate::new(
+                radical.clone(),
+                vec![pinyin.to_string()],
+                CandidateKind::Radical,
+                RADICAL_SCORE,
+                CandidateSource::RadicalDictionary,
+            ));
+        }
+        
+        results
+    }
+
+    /// 检查是否应该添加部首候选项
+    /// 当输入为单音节，或者分词结果只有一个候选项的一个音节时
+    pub fn should_add_radical(&self, input: &str, segment_results: &[crate::segmentation::SegmentResult]) -> bool {
+        // 情况1：输入为单音节
+        if !input.contains(char::is_whitespace) && self.syllable_set.contains(input) {
+            return true;
+        }
+        
+        // 情况2：分词结果只有一个候选项且只有一个音节
+        if segment_results.len() == 1 && segment_results[0].segments.len() == 1 {
+            return true;
+        }
+        
+        false
+    }
+
+    /// 获取读音为 pinyin 的所有部首，按彝文部首区块中的顺序排列
+    pub fn get_radical_candidates(&self, pinyin: &str) -> &[String] {
+        self.radical_pinyin_index.get(pinyin).map_or(&[], Vec::as_slice)
+    }
+
+    /// 模糊查询：查找包含指定拼音前缀的所有候选
+    pub fn fuzzy_query(&self, prefix: &str) -> Vec<(String, Vec<String>)> {
+        let mut results: Vec<(String, Vec<String>)> = self.syllable_trie
+            .completions(prefix)
+            .into_iter()
+            .filter_map(|pinyin| {
+                let yi_chars = self.pinyin_index.get(&pinyin)?.clone();
+                Some((pinyin, yi_chars))
+            })
+            .collect();
+        
+        results.sort_by_key(|a| a.0.len());
+        results
+    }
+}
+
+impl Default for YiIME {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 两个共用读音 ggop 的部首，故意不按区块顺序排列，区块中靠后的部首频率更高
+    const SHARED_READING: &str = r#"{"version": 2, "entries": {
+        "꒖": {"readings": ["ggop"], "frequency": 900},
+        "꒐": {"readings": ["ggop", "qot"], "frequency": 1}
+    }}"#;
+
+    fn ime_with_radicals(radicals: &str) -> (YiIME, LoadReport) {
+        let mut ime = YiIME::new();
+        ime.load_dictionary_from_str(YI_SYLLABLE_DICT).unwrap();
+        let report = ime.load_radical_dictionary_with_mode(radicals, LoadMode::Strict).unwrap();
+        (ime, report)
+    }
+
+    fn radicals(candidates: &[Candidate]) -> Vec<&str> {
+        candidates
+            .iter()
+            .filter(|candidate| candidate.kind == CandidateKind::Radical)
+            .map(|candidate| candidate.text.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn single_syllable_offers_radical() {
+        let ime = YiIME::with_builtin_dictionaries().unwrap();
+        let candidates = ime.smart_convert("ggop");
+        assert_eq!(candidates.iter().map(|c| c.text.as_str()).collect::<Vec<_>>(), ["꒖", "ꈥ"]);
+        assert_eq!(candidates[0].kind, CandidateKind::Radical);
+        assert_eq!(candidates[1].kind, CandidateKind::Syllable);
+    }
+
+    #[test]
+    fn radicals_sharing_a_reading_are_all_kept() {
+        let (ime, report) = ime_with_radicals(SHARED_READING);
+        assert_eq!(report.loaded, 2);
+        assert_eq!(ime.get_radical_candidates("ggop"), ["꒐", "꒖"]);
+        assert_eq!(ime.get_radical_candidates("qot"), ["꒐"]);
+        assert_eq!(ime.radical_dictionary["꒐"], "ggop");
+    }
+
+    #[test]
+    fn shared_reading_is_only_a_warning() {
+        let (_, report) = ime_with_radicals(SHARED_READING);
+        assert_eq!(report.warnings.len(), 1);
+        assert!(matches!(
+            &report.warnings[0],
+            DictError::DuplicatePinyin { pinyin, existing, duplicate }
+                if pinyin == "ggop" && existing == "꒖" && duplicate == "꒐"
+        ));
+    }
+
+    #[test]
+    fn radicals_stay_in_block_order_despite_frequency() {
+        let (ime, _) = ime_with_radicals(SHARED_READING);
+        assert_eq!(radicals(&ime.query_by_pinyin("ggop")), ["꒐", "꒖"]);
+        assert_eq!(radicals(&ime.candidates("ggop")), ["꒐", "꒖"]);
+        assert_eq!(radicals(&ime.smart_convert("ggop")), ["꒐", "꒖"]);
+        // 声母联想中的部首同样按区块顺序
+        assert_eq!(radicals(&ime.candidates("gg")), ["꒐", "꒖"]);
+    }
+}
@@ -0,0 +1,258 @@
+use crate::dictionary::DictError;
+use crate::ime::YiIME;
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::sync::Arc;
+
+/// 二元模型文件的格式版本
+pub const BIGRAM_MODEL_VERSION: u64 = 1;
+
+/// 二元模型文件中表示输入开头的音节
+pub const SENTENCE_START: &str = "<s>";
+
+/// 分词时为音节序列打分的语言模型
+pub trait LanguageModel: fmt::Debug + Send + Sync {
+    /// 前一个音节为 prev（None 表示输入开头）时出现 syllable 的概率，必须大于 0
+    fn probability(&self, prev: Option<&str>, syllable: &str) -> f32;
+}
+
+/// 音节二元模型：按 Witten-Bell 方法用一元概率平滑二元概率
+///
+/// 模型文件为纯文本，每行一条计数：
+///
+/// ```text
+/// yi-bigram 1
+/// 1 nuo 120
+/// 2 <s> nuo 35
+/// 2 nuo su 80
+/// ```
+///
+/// 第一行为文件头，`1` 开头的行为音节的出现次数，`2` 开头的行为相邻两个音节的出现次数，
+/// `<s>` 表示输入开头；空行和以 # 开头的行被忽略。
+#[derive(Debug, Clone, Default)]
+pub struct BigramModel {
+    /// 音节 -> 出现次数
+    unigrams: HashMap<String, u64>,
+    /// 前一个音节 -> (音节 -> 相邻出现次数)
+    bigrams: HashMap<String, HashMap<String, u64>>,
+    /// 前一个音节 -> 以它开头的二元计数之和
+    context_totals: HashMap<String, u64>,
+    /// 所有音节的出现次数之和
+    total: u64,
+}
+
+impl BigramModel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 从模型文件加载
+    pub fn load(file_path: &str) -> Result<Self, DictError> {
+        let content = fs::read_to_string(file_path)?;
+        Self::parse(&content)
+    }
+
+    /// 解析模型文件的内容
+    pub fn parse(content: &str) -> Result<Self, DictError> {
+        let mut lines = content
+            .lines()
+            .enumerate()
+            .map(|(number, line)| (number + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let version = match lines.next().map(|(_, line)| line.split_whitespace().collect::<Vec<_>>()) {
+            Some(header) if header.len() == 2 && header[0] == "yi-bigram" => header[1].to_string(),
+            _ => return Err(DictError::InvalidSchema("缺少 yi-bigram 文件头".to_string())),
+        };
+        if version.parse::<u64>().ok() != Some(BIGRAM_MODEL_VERSION) {
+            return Err(DictError::UnknownSchemaVersion(version));
+        }
+
+        let mut model = Self::new();
+        for (number, line) in lines {
+            let invalid = |message: &str| DictError::InvalidEntry {
+                key: format!("第 {} 行", number),
+                message: message.to_string(),
+            };
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let count = fields
+                .last()
+                .and_then(|count| count.parse::<u64>().ok())
+                .ok_or_else(|| invalid("最后一列必须是非负整数"))?;
+            match fields.as_slice() {
+                ["1", syllable, _] => model.add_unigram(syllable, count),
+                ["2", prev, syllable, _] => model.add_bigram(prev, syllable, count),
+                _ => return Err(invalid("应为「1 音节 次数」或「2 前一音节 音节 次数」")),
+            }
+        }
+        Ok(model)
+    }
+
+    /// 增加一个音节的出现次数
+    pub fn add_unigram(&mut self, syllable: &str, count: u64) {
+        *self.unigrams.entry(syllable.to_string()).or_default() += count;
+        self.total += count;
+    }
+
+    /// 增加相邻两个音节的出现次数，prev 为 [`SENTENCE_START`] 表示输入开头
+    pub fn add_bigram(&mut self, prev: &str, syllable: &str, count: u64) {
+        *self
+            .bigrams
+            .entry(prev.to_string())
+            .or_default()
+            .entry(syllable.to_string())
+            .or_default() += count;
+        *self.context_totals.entry(prev.to_string()).or_default() += count;
+    }
+
+    /// 导出为模型文件，各行按音节排序，因此相同的计数总是得到相同的文件
+    pub fn export(&self) -> String {
+        let mut output = format!("yi-bigram {}\n", BIGRAM_MODEL_VERSION);
+
+        let mut unigrams: Vec<(&String, &u64)> = self.unigrams.iter().collect();
+        unigrams.sort();
+        for (syllable, count) in unigrams {
+            output.push_str(&format!("1 {} {}\n", syllable, count));
+        }
+
+        let mut bigrams: Vec<(&String, &String, &u64)> = self
+            .bigrams
+            .iter()
+            .flat_map(|(prev, next)| next.iter().map(move |(syllable, count)| (prev, syllable, count)))
+            .collect();
+        bigrams.sort();
+        for (prev, syllable, count) in bigrams {
+            output.push_str(&format!("2 {} {} {}\n", prev, syllable, count));
+        }
+        output
+    }
+
+    /// 不同音节的个数
+    pub fn vocabulary_size(&self) -> usize {
+        self.unigrams.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.unigrams.is_empty() && self.bigrams.is_empty()
+    }
+
+    /// 加一平滑的一元概率，未出现过的音节也有非零概率
+    fn unigram_probability(&self, syllable: &str) -> f64 {
+        let count = self.unigrams.get(syllable).copied().unwrap_or(0);
+        (count + 1) as f64 / (self.total + self.unigrams.len() as u64 + 1) as f64
+    }
+}
+
+impl LanguageModel for BigramModel {
+    fn probability(&self, prev: Option<&str>, syllable: &str) -> f32 {
+        let unigram = self.unigram_probability(syllable);
+        let context = prev.unwrap_or(SENTENCE_START);
+        let (Some(followers), Some(&context_total)) = (self.bigrams.get(context), self.context_totals.get(context)) else {
+            return unigram as f32;
+        };
+
+        // 前一个音节之后出现过的不同音节越多，越依赖一元概率
+        let types = followers.len() as f64;
+        let count = followers.get(syllable).copied().unwrap_or(0) as f64;
+        ((count + types * unigram) / (context_total as f64 + types)) as f32
+    }
+}
+
+impl YiIME {
+    /// 输入片段交给语言模型的拼音：无声调输入时取最可能的读音
+    pub(crate) fn model_reading(&self, segment: &str) -> String {
+        if self.syllable_set.contains(segment) {
+            return segment.to_string();
+        }
+        self.segment_readings(segment)
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| segment.to_string())
+    }
+
+    /// 设置分词时使用的语言模型；没有语言模型时按音节长度估计置信度
+    pub fn set_language_model(&mut self, model: impl LanguageModel + 'static) {
+        self.language_model = Some(Arc::new(model));
+    }
+
+    /// 移除语言模型，恢复按音节长度估计置信度
+    pub fn clear_language_model(&mut self) {
+        self.language_model = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MODEL: &str = "yi-bigram 1
+# 注释和空行被忽略
+
+1 nuo 3
+1 su 1
+2 nuo su 3
+2 nuo bi 1
+";
+
+    fn assert_close(actual: f32, expected: f64) {
+        assert!((actual as f64 - expected).abs() < 1e-6, "{} != {}", actual, expected);
+    }
+
+    #[test]
+    fn parse_and_export_round_trip() {
+        let model = BigramModel::parse(MODEL).unwrap();
+        assert_eq!(model.vocabulary_size(), 2);
+        let exported = model.export();
+        assert_eq!(exported, "yi-bigram 1\n1 nuo 3\n1 su 1\n2 nuo bi 1\n2 nuo su 3\n");
+        assert_eq!(BigramModel::parse(&exported).unwrap().export(), exported);
+    }
+
+    #[test]
+    fn witten_bell_probabilities() {
+        let model = BigramModel::parse(MODEL).unwrap();
+        // 一元概率为 (次数 + 1) / (4 + 2 + 1)；nuo 之后出现过 2 种音节，共 4 次
+        assert_close(model.probability(Some("nuo"), "su"), (3.0 + 2.0 * 2.0 / 7.0) / 6.0);
+        assert_close(model.probability(Some("nuo"), "a"), (2.0 / 7.0) / 6.0);
+        // 没有出现过的上下文退回一元概率
+        assert_close(model.probability(Some("su"), "nuo"), 4.0 / 7.0);
+        assert_close(model.probability(None, "a"), 1.0 / 7.0);
+        assert!(model.probability(Some("nuo"), "su") > model.probability(Some("nuo"), "bi"));
+    }
+
+    #[test]
+    fn rejects_bad_header() {
+        assert!(matches!(
+            BigramModel::parse("yi-bigram 2\n1 nuo 3\n"),
+            Err(DictError::UnknownSchemaVersion(version)) if version == "2"
+        ));
+        assert!(matches!(BigramModel::parse("1 nuo 3\n"), Err(DictError::InvalidSchema(_))));
+        assert!(matches!(BigramModel::parse(""), Err(DictError::InvalidSchema(_))));
+        assert!(matches!(
+            BigramModel::parse("yi-bigram 1\n1 nuo three\n"),
+            Err(DictError::InvalidEntry { key, .. }) if key == "第 2 行"
+        ));
+    }
+
+    #[test]
+    fn lattice_scores_paths_with_model() {
+        let mut ime = YiIME::with_builtin_dictionaries().unwrap();
+        assert_eq!(ime.segment_pinyin("ayixiep")[0].segments, ["a", "yix", "iep"]);
+
+        let mut model = BigramModel::new();
+        for (prev, syllable) in [(SENTENCE_START, "a"), ("a", "yi"), ("yi", "xiep")] {
+            model.add_unigram(syllable, 10);
+            model.add_bigram(prev, syllable, 10);
+        }
+        let expected = model.probability(None, "a") as f64
+            * model.probability(Some("a"), "yi") as f64
+            * model.probability(Some("yi"), "xiep") as f64;
+        ime.set_language_model(model);
+        let best = ime.segment_pinyin("ayixiep").remove(0);
+        assert_eq!(best.segments, ["a", "yi", "xiep"]);
+        assert_close(best.confidence, expected);
+
+        ime.clear_language_model();
+        assert_eq!(ime.segment_pinyin("ayixiep")[0].segments, ["a", "yix", "iep"]);
+    }
+}
@@ -0,0 +1,631 @@
+use crate::ime::{YiIME, ITERATION_MARK};
+use crate::segmentation::{is_syllable_delimiter, SegmentResult, SEGMENT_LIMIT};
+use std::cmp::Reverse;
+use std::collections::HashSet;
+
+/// 词库中的词作为一条边时的置信度，与词包含的音节数无关
+const WORD_CONFIDENCE: f32 = 0.95;
+
+/// 替字符号 ꀕ 作为一条边时的置信度（没有语言模型时），低于同样长度的完整音节
+const ITERATION_CONFIDENCE: f32 = 0.5;
+
+/// 分词网格中的一条边：覆盖输入的 [start, end) 区间
+#[derive(Debug, Clone)]
+pub struct LatticeEdge {
+    pub start: usize,
+    pub end: usize,
+    /// 边内各音节的结束位置（普通音节只有 end，歧义分割为 [split, end]）
+    pub cuts: Vec<usize>,
+    /// 没有语言模型时按音节长度估计的置信度
+    pub confidence: f32,
+    /// 边内各音节交给语言模型的拼音（纠错时为纠正后的拼音，无声调输入时为最可能的读音）
+    pub readings: Vec<String>,
+    /// 纠错和无声调匹配的折扣，使用语言模型时与模型给出的概率相乘
+    pub penalty: f32,
+    /// 纠错得到的音节：输入片段被纠正后的拼音
+    pub corrected: Option<String>,
+    /// 纠错的编辑代价，其他边为 0
+    pub edit_cost: f32,
+    /// 是否为词库中的多音节词（cuts 为词内各音节的结束位置）
+    pub word: bool,
+    /// 是否为替字符号 ꀕ：读音与路径上的前一个音节相同，只能接在音节之后
+    pub iteration: bool,
+}
+
+impl LatticeEdge {
+    /// 音节分隔符：不包含音节（cuts 为空），只把路径延伸过分隔符
+    fn delimiter(start: usize) -> Self {
+        LatticeEdge {
+            start,
+            end: start + 1,
+            cuts: Vec::new(),
+            confidence: 1.0,
+            readings: Vec::new(),
+            penalty: 1.0,
+            corrected: None,
+            edit_cost: 0.0,
+            word: false,
+            iteration: false,
+        }
+    }
+
+    /// 替字符号按键：作为一个读音待定的音节
+    fn iteration(start: usize) -> Self {
+        LatticeEdge {
+            start,
+            end: start + 1,
+            cuts: vec![start + 1],
+            confidence: ITERATION_CONFIDENCE,
+            readings: Vec::new(),
+            penalty: 1.0,
+            corrected: None,
+            edit_cost: 0.0,
+            word: false,
+            iteration: true,
+        }
+    }
+}
+
+/// 到达某个节点的部分路径
+#[derive(Debug, Clone)]
+struct PartialPath {
+    /// 路径上每个音节的结束位置
+    cuts: Vec<usize>,
+    /// 纠错得到的音节：(音节序号, 纠正后的拼音)
+    corrections: Vec<(usize, String)>,
+    /// 词库中的词覆盖的音节区间
+    words: Vec<(usize, usize)>,
+    /// 词库中的词覆盖的输入字符数
+    word_chars: usize,
+    /// 纠错的编辑代价之和
+    edit_cost: f32,
+    /// 替字符号 ꀕ 的音节序号
+    iterations: Vec<usize>,
+    /// 最后一个音节交给语言模型的拼音
+    last_reading: Option<String>,
+    confidence: f32,
+}
+
+impl PartialPath {
+    /// 位于输入开头的空路径
+    fn start() -> Self {
+        PartialPath {
+            cuts: Vec::new(),
+            corrections: Vec::new(),
+            words: Vec::new(),
+            word_chars: 0,
+            edit_cost: 0.0,
+            iterations: Vec::new(),
+            last_reading: None,
+            confidence: 1.0,
+        }
+    }
+}
+
+/// 部分路径沿一条边的扩展，排序时不必复制路径
+struct Extension<'a> {
+    prev: &'a PartialPath,
+    edge: &'a LatticeEdge,
+    confidence: f32,
+}
+
+impl Extension<'_> {
+    fn is_corrected(&self) -> bool {
+        !self.prev.corrections.is_empty() || self.edge.corrected.is_some()
+    }
+
+    fn cuts(&self) -> impl Iterator<Item = &usize> {
+        self.prev.cuts.iter().chain(&self.edge.cuts)
+    }
+
+    fn corrections(&self) -> impl Iterator<Item = (usize, &str)> {
+        let index = self.prev.cuts.len();
+        self.prev
+            .corrections
+            .iter()
+            .map(|(i, pinyin)| (*i, pinyin.as_str()))
+            .chain(self.edge.corrected.as_deref().map(|pinyin| (index, pinyin)))
+    }
+
+    fn words(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let start = self.prev.cuts.len();
+        let word = self.edge.word.then_some((start, start + self.edge.cuts.len()));
+        self.prev.words.iter().copied().chain(word)
+    }
+
+    fn iterations(&self) -> impl Iterator<Item = usize> + '_ {
+        let index = self.prev.cuts.len();
+        self.prev.iterations.iter().copied().chain(self.edge.iteration.then_some(index))
+    }
+
+    fn word_chars(&self) -> usize {
+        let edge_chars = if self.edge.word { self.edge.end - self.edge.start } else { 0 };
+        self.prev.word_chars + edge_chars
+    }
+
+    fn edit_cost(&self) -> f32 {
+        self.prev.edit_cost + self.edge.edit_cost
+    }
+
+    fn to_path(&self) -> PartialPath {
+        PartialPath {
+            cuts: self.cuts().copied().collect(),
+            corrections: self.corrections().map(|(i, pinyin)| (i, pinyin.to_string())).collect(),
+            words: self.words().collect(),
+            word_chars: self.word_chars(),
+            edit_cost: self.edit_cost(),
+            iterations: self.iterations().collect(),
+            // 越过分隔符和替字符号的路径保留原来的最后一个音节
+            last_reading: self.edge.readings.last().or(self.prev.last_reading.as_ref()).cloned(),
+            confidence: self.confidence,
+        }
+    }
+}
+
+/// 分词网格：节点为输入中的位置，边为可能的音节
+///
+/// 词库中的词作为覆盖多个音节的整体边；路径先比较词覆盖的输入长度，再比较置信度。
+/// 加载了语言模型时，路径的置信度为模型给出的音节序列概率，否则按音节长度估计。
+/// 每个节点只保留最好的 k 条互不相同的部分路径（k-best Viterbi），
+/// 因此时间和内存随输入长度线性增长。开启纠错时，包含纠错音节的路径单独保留 k 条，
+/// 不会挤掉精确匹配的路径；纠错路径先按编辑代价之和排序，再比较词和置信度。
+///
+/// 到达某个节点的边和路径只依赖它之前的输入，因此网格可以逐字扩展：
+/// 追加一个字母只计算结束于新位置的边和路径，退格时直接丢弃最后一个节点。
+#[derive(Debug, Clone)]
+pub struct Lattice {
+    chars: Vec<char>,
+    /// edges[i] 为所有结束于位置 i 的边
+    edges: Vec<Vec<LatticeEdge>>,
+    /// best[i] 为到达位置 i 的最好的若干条部分路径
+    best: Vec<Vec<PartialPath>>,
+    /// 每个节点保留的路径数
+    beam: usize,
+}
+
+impl Lattice {
+    /// 空网格，每个节点保留 beam 条精确路径（和 beam 条纠错路径）
+    pub fn new(beam: usize) -> Self {
+        Lattice {
+            chars: Vec::new(),
+            edges: vec![Vec::new()],
+            best: vec![vec![PartialPath::start()]],
+            beam,
+        }
+    }
+
+    /// 根据输入构建分词网格
+    pub fn build(ime: &YiIME, input: &str) -> Self {
+        let mut lattice = Self::new(SEGMENT_LIMIT);
+        for ch in input.chars() {
+            lattice.push(ime, ch);
+        }
+        lattice
+    }
+
+    /// 在输入末尾追加一个字符，只计算结束于新位置的边和路径
+    pub fn push(&mut self, ime: &YiIME, ch: char) {
+        self.chars.push(ch);
+        let end = self.chars.len();
+        self.edges.push(Self::edges_ending_at(ime, &self.chars, end));
+        let paths = self.best_paths(ime, &self.best, end, self.beam);
+        self.best.push(paths);
+    }
+
+    /// 删除输入的最后一个字符，之前的节点不受影响
+    pub fn pop(&mut self) -> Option<char> {
+        let ch = self.chars.pop()?;
+        self.edges.pop();
+        self.best.pop();
+        Some(ch)
+    }
+
+    /// 清空输入
+    pub fn clear(&mut self) {
+        self.chars.clear();
+        self.edges.truncate(1);
+        self.best.truncate(1);
+    }
+
+    /// 计算所有结束于位置 end 的边，chars 为截至 end 的输入
+    ///
+    /// 音节、歧义分割和纠错的边都不跨过音节分隔符；词库中的词可以跨过，
+    /// 但分隔符必须位于词内的音节之间。
+    fn edges_ending_at(ime: &YiIME, chars: &[char], end: usize) -> Vec<LatticeEdge> {
+        if is_syllable_delimiter(chars[end - 1]) {
+            return vec![LatticeEdge::delimiter(end - 1)];
+        }
+
+        let mut edges = Vec::new();
+        // 替字符号按键也可能是音节的一部分（如 w 开头的 wa），两种边都保留
+        if chars[end - 1] == ime.options.iteration_key {
+            edges.push(LatticeEdge::iteration(end - 1));
+        }
+
+        // 无声调输入：不带声调标记的音节也可以匹配
+        let is_syllable = |start: usize, end: usize| {
+            let segment: String = chars[start..end].iter().collect();
+            ime.syllable_trie.contains(&segment) || (ime.options.toneless && ime.toneless_trie.contains(&segment))
+        };
+        let syllable_len = ime.syllable_trie.max_len().max(if ime.options.toneless {
+            ime.toneless_trie.max_len()
+        } else {
+            0
+        });
+
+        // 单个音节
+        for start in end.saturating_sub(syllable_len)..end {
+            if !is_syllable(start, end) {
+                continue;
+            }
+            let segment: String = chars[start..end].iter().collect();
+            edges.push(LatticeEdge {
+                start,
+                end,
+                cuts: vec![end],
+                confidence: ime.calculate_segment_confidence(&segment, end - start)
+                    * ime.toneless_penalty(&segment),
+                readings: vec![ime.model_reading(&segment)],
+                penalty: ime.toneless_penalty(&segment),
+                corrected: None,
+                edit_cost: 0.0,
+                word: false,
+                iteration: false,
+            });
+        }
+
+        // 词库中的词：同一段输入的每种音节划分各为一条边
+        let mut letters = 0;
+        for start in (0..end).rev() {
+            if is_syllable_delimiter(chars[start]) {
+                continue;
+            }
+            letters += 1;
+            if letters > ime.lexicon.max_len() {
+                break;
+            }
+            let input: String = chars[start..end].iter().filter(|&&c| !is_syllable_delimiter(c)).collect();
+            let mut divisions: Vec<&Vec<String>> = Vec::new();
+            for word in ime.lexicon.lookup(&input) {
+                if !divisions.contains(&&word.pinyin) {
+                    divisions.push(&word.pinyin);
+                }
+            }
+            for pinyin in divisions {
+                let Some(cuts) = Self::word_cuts(chars, start, end, pinyin) else {
+                    continue;
+                };
+                edges.push(LatticeEdge {
+                    start,
+                    end,
+                    cuts,
+                    confidence: WORD_CONFIDENCE,
+                    readings: pinyin.clone(),
+                    penalty: 1.0,
+                    corrected: None,
+                    edit_cost: 0.0,
+                    word: true,
+                    iteration: false,
+                });
+            }
+        }
+
+        // 处理歧义字符的特殊情况：两个相邻音节作为一条整体边
+        for split in end.saturating_sub(syllable_len)..end {
+            if !is_syllable(split, end) {
+                continue;
+            }
+            for start in split.saturating_sub(syllable_len)..split {
+                let has_ambiguous = chars[start..end].iter().any(|&c| YiIME::is_ambiguous_char(c));
+                if !has_ambiguous || !is_syllable(start, split) {
+                    continue;
+                }
+                let left: String = chars[start..split].iter().collect();
+                let right: String = chars[split..end].iter().collect();
+                edges.push(LatticeEdge {
+                    start,
+                    end,
+                    cuts: vec![split, end],
+                    confidence: ime.calculate_ambiguous_confidence(&left, &right)
+                        * ime.toneless_penalty(&left)
+                        * ime.toneless_penalty(&right),
+                    readings: vec![ime.model_reading(&left), ime.model_reading(&right)],
+                    penalty: ime.toneless_penalty(&left) * ime.toneless_penalty(&right),
+                    corrected: None,
+                    edit_cost: 0.0,
+                    word: false,
+                    iteration: false,
+                });
+            }
+        }
+
+        // 纠错：与字典音节相差一两次编辑的片段作为带惩罚的备选边
+        if let Some(correction) = &ime.options.correction {
+            let longest = ime.syllable_trie.max_len() + correction.max_edits;
+            for start in end.saturating_sub(longest)..end {
+                if chars[start..end].iter().any(|&c| is_syllable_delimiter(c)) {
+                    continue;
+                }
+                let segment: String = chars[start..end].iter().collect();
+                for (pinyin, cost) in ime.corrections(&segment) {
+                    let length = pinyin.chars().count();
+                    let penalty = correction.penalty.powf(cost);
+                    edges.push(LatticeEdge {
+                        start,
+                        end,
+                        cuts: vec![end],
+                        confidence: ime.calculate_segment_confidence(&pinyin, length) * penalty,
+                        readings: vec![pinyin.clone()],
+                        penalty,
+                        corrected: Some(pinyin),
+                        edit_cost: cost,
+                        word: false,
+                        iteration: false,
+                    });
+                }
+            }
+        }
+
+        edges
+    }
+
+    /// 词的各音节在输入 [start, end) 中的结束位置；词内的分隔符不在音节之间时返回 None
+    fn word_cuts(chars: &[char], start: usize, end: usize, pinyin: &[String]) -> Option<Vec<usize>> {
+        let mut cuts = Vec::with_capacity(pinyin.len());
+        let mut position = start;
+        for syllable in pinyin {
+            while position < end && is_syllable_delimiter(chars[position]) {
+                position += 1;
+            }
+            let next = position + syllable.chars().count();
+            if chars.get(position..next)?.iter().any(|&c| is_syllable_delimiter(c)) {
+                return None;
+            }
+            position = next;
+            cuts.push(position);
+        }
+        (position == end).then_some(cuts)
+    }
+
+    /// 输入长度（字符数）
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chars.is_empty()
+    }
+
+    /// 所有结束于位置 end 的边
+    pub fn edges_to(&self, end: usize) -> &[LatticeEdge] {
+        &self.edges[end]
+    }
+
+    /// 提取最好的 k 个互不相同的完整分词方案
+    pub fn k_best(&self, ime: &YiIME, k: usize) -> Vec<SegmentResult> {
+        self.k_best_prefix(ime, self.chars.len(), k)
+    }
+
+    /// 提取输入前 end 个字符最好的 k 个互不相同的分词方案
+    ///
+    /// k 与构建网格时的 beam 相同时直接使用已经计算好的路径。
+    pub fn k_best_prefix(&self, ime: &YiIME, end: usize, k: usize) -> Vec<SegmentResult> {
+        if end == 0 || end > self.chars.len() || k == 0 {
+            return Vec::new();
+        }
+
+        let recomputed;
+        let paths = if k == self.beam {
+            &self.best[end]
+        } else {
+            let mut best = vec![vec![PartialPath::start()]];
+            for i in 1..=end {
+                let paths = self.best_paths(ime, &best, i, k);
+                best.push(paths);
+            }
+            recomputed = best;
+            &recomputed[end]
+        };
+
+        // 只由分隔符组成的路径不包含音节，不是分词方案；
+        // 不同位置的纠错可能得到相同的拼音，只保留第一个
+        let mut seen = HashSet::new();
+        paths
+            .iter()
+            .filter(|path| !path.cuts.is_empty())
+            .map(|path| self.to_segment_result(ime, path))
+            .filter(|result| {
+                seen.insert((result.segments.clone(), result.words.clone(), result.iterations.clone()))
+            })
+            .collect()
+    }
+
+    /// 由到达之前各节点的路径 best 计算到达位置 end 的最好的 k 条路径
+    fn best_paths(&self, ime: &YiIME, best: &[Vec<PartialPath>], end: usize, k: usize) -> Vec<PartialPath> {
+        let mut extensions = Vec::new();
+        for edge in &self.edges[end] {
+            for prev in &best[edge.start] {
+                // 替字符号之前必须有音节
+                if edge.iteration && prev.cuts.is_empty() {
+                    continue;
+                }
+                extensions.push(Extension {
+                    prev,
+                    edge,
+                    confidence: prev.confidence * Self::edge_score(ime, prev, edge),
+                });
+            }
+        }
+        // 精确路径和纠错路径各保留 k 条，精确路径在前
+        let (exact, corrected): (Vec<_>, Vec<_>) = extensions
+            .into_iter()
+            .partition(|extension| !extension.is_corrected());
+        let mut paths = Self::top_k(exact, k);
+        paths.extend(Self::top_k(corrected, k));
+        paths
+    }
+
+    /// 部分路径沿一条边扩展时乘上的分数
+    ///
+    /// 有语言模型时为边内各音节依次接在路径之后的概率（再乘以折扣），
+    /// 替字符号为前一个音节重复出现的概率；否则为按音节长度估计的置信度。
+    fn edge_score(ime: &YiIME, prev: &PartialPath, edge: &LatticeEdge) -> f32 {
+        let Some(model) = &ime.language_model else {
+            return edge.confidence;
+        };
+        let mut last = prev.last_reading.as_deref();
+        if edge.iteration {
+            return edge.penalty * last.map_or(1.0, |reading| model.probability(Some(reading), reading));
+        }
+        let mut score = edge.penalty;
+        for reading in &edge.readings {
+            score *= model.probability(last, reading);
+            last = Some(reading);
+        }
+        score
+    }
+
+    /// 按编辑代价、词覆盖的长度和置信度排序，去除相同的分词方案并只保留前 k 个
+    ///
+    /// 先对扩展排序，只为最终保留的路径分配内存。
+    fn top_k(mut extensions: Vec<Extension>, k: usize) -> Vec<PartialPath> {
+        extensions.sort_by(|a, b| {
+            a.edit_cost()
+                .total_cmp(&b.edit_cost())
+                .then_with(|| Reverse(a.word_chars()).cmp(&Reverse(b.word_chars())))
+                .then_with(|| b.confidence.total_cmp(&a.confidence))
+                .then_with(|| a.cuts().cmp(b.cuts()))
+                .then_with(|| a.corrections().cmp(b.corrections()))
+                .then_with(|| a.words().cmp(b.words()))
+                .then_with(|| a.iterations().cmp(b.iterations()))
+        });
+
+        let mut seen = HashSet::new();
+        let mut paths = Vec::with_capacity(k);
+        for extension in extensions {
+            if paths.len() >= k {
+                break;
+            }
+            let path = extension.to_path();
+            let key = (path.cuts.clone(), path.corrections.clone(), path.words.clone(), path.iterations.clone());
+            if seen.insert(key) {
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    fn to_segment_result(&self, ime: &YiIME, path: &PartialPath) -> SegmentResult {
+        let mut segments = Vec::with_capacity(path.cuts.len());
+        let mut yi_chars = Vec::with_capacity(path.cuts.len());
+        let mut start = 0;
+        for (index, &end) in path.cuts.iter().enumerate() {
+            if path.iterations.contains(&index) {
+                // 替字符号重复前一个音节的读音
+                let reading = segments.last().cloned().unwrap_or_default();
+                yi_chars.push(vec![ITERATION_MARK.to_string()]);
+                segments.push(reading);
+            } else {
+                let segment: String = match path.corrections.iter().find(|(i, _)| *i == index) {
+                    Some((_, pinyin)) => pinyin.clone(),
+                    None => self.chars[start..end].iter().filter(|&&c| !is_syllable_delimiter(c)).collect(),
+                };
+                yi_chars.push(ime.segment_chars(&segment));
+                segments.push(segment);
+            }
+            start = end;
+        }
+        SegmentResult {
+            segments,
+            yi_chars,
+            confidence: path.confidence,
+            corrected: !path.corrections.is_empty(),
+            edit_cost: path.edit_cost,
+            words: path.words.clone(),
+            iterations: path.iterations.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::correction::CorrectionOptions;
+
+    fn ime() -> YiIME {
+        YiIME::with_builtin_dictionaries().unwrap()
+    }
+
+    fn segments(results: &[SegmentResult]) -> Vec<Vec<String>> {
+        results.iter().map(|result| result.segments.clone()).collect()
+    }
+
+    #[test]
+    fn corrected_paths_follow_exact_paths() {
+        let mut ime = ime();
+        ime.options.correction = Some(CorrectionOptions::default());
+        let results = Lattice::build(&ime, "nuosu").k_best(&ime, SEGMENT_LIMIT);
+        assert_eq!(results[0].segments, ["nuo", "su"]);
+        assert!(!results[0].corrected);
+
+        let first_corrected = results.iter().position(|result| result.corrected).unwrap();
+        assert!(results[first_corrected..].iter().all(|result| result.corrected));
+
+        // 纠错路径不会挤掉精确路径：精确部分与关闭纠错时完全相同
+        ime.options.correction = None;
+        let exact = Lattice::build(&ime, "nuosu").k_best(&ime, SEGMENT_LIMIT);
+        assert_eq!(segments(&results[..first_corrected]), segments(&exact));
+    }
+
+    #[test]
+    fn k_best_is_ranked_and_distinct() {
+        let ime = ime();
+        let lattice = Lattice::build(&ime, "ayixiep");
+        let results = lattice.k_best(&ime, SEGMENT_LIMIT);
+        assert!(results.len() > 1);
+        for pair in results.windows(2) {
+            assert!(pair[0].confidence >= pair[1].confidence);
+        }
+        let distinct: HashSet<Vec<String>> = segments(&results).into_iter().collect();
+        assert_eq!(distinct.len(), results.len());
+
+        // 保留的路径数较少时得到同样顺序的前几个方案
+        for k in 1..results.len() {
+            assert_eq!(segments(&lattice.k_best(&ime, k)), segments(&results[..k]));
+        }
+    }
+
+    #[test]
+    fn words_rank_before_higher_confidence_paths() {
+        let ime = ime();
+        let results = Lattice::build(&ime, "nuosu").k_best(&ime, SEGMENT_LIMIT);
+        assert_eq!(results[0].words, [(0, 2)]);
+        assert!(results[1..].iter().all(|result| result.words.is_empty()));
+    }
+
+    #[test]
+    fn prefix_matches_full_segmentation() {
+        let ime = ime();
+        let input = "nuosuhxitbbu'ap";
+        let lattice = Lattice::build(&ime, input);
+        for end in 1..=input.len() {
+            assert_eq!(
+                segments(&lattice.k_best_prefix(&ime, end, SEGMENT_LIMIT)),
+                segments(&ime.segment_pinyin(&input[..end])),
+                "{}",
+                &input[..end]
+            );
+        }
+    }
+
+    #[test]
+    fn push_and_pop_match_build() {
+        let ime = ime();
+        let mut lattice = Lattice::build(&ime, "shyrxnuo");
+        lattice.pop();
+        lattice.pop();
+        lattice.push(&ime, 'i');
+        let rebuilt = Lattice::build(&ime, "shyrxni");
+        assert_eq!(segments(&lattice.k_best(&ime, SEGMENT_LIMIT)), segments(&rebuilt.k_best(&ime, SEGMENT_LIMIT)));
+    }
+}
@@ -0,0 +1,353 @@
+use crate::candidates::{Candidate, CandidateKind, CandidateSource, MAX_CANDIDATES};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// 输入历史文件的格式版本
+const HISTORY_VERSION: u64 = 1;
+
+/// 最多保留的选择记录数，超出时淘汰最久未用的记录
+const MAX_SELECTIONS: usize = 10_000;
+
+/// 时间衰减的尺度：经过这么多次选择后，一条记录的权重减半
+const RECENCY_SCALE: f32 = 200.0;
+
+/// 选择过但已不在候选列表中的候选项的基础分数
+const HISTORY_SCORE: f32 = 1.0;
+
+/// 一条选择记录：输入某段拼音时选择了某个候选项
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Selection {
+    /// 选择时输入缓冲区中的拼音
+    pub input: String,
+    /// 选择的彝文
+    pub text: String,
+    /// 与彝文逐字对应的拼音音节
+    pub pinyin_segments: Vec<String>,
+    /// 选择次数
+    pub count: u64,
+    /// 最近一次选择的序号（见 [`UserHistory::clock`]）
+    pub last_used: u64,
+}
+
+impl Selection {
+    /// 排序权重：次数越多、越近使用的记录权重越高
+    pub fn weight(&self, clock: u64) -> f32 {
+        let age = clock.saturating_sub(self.last_used) as f32;
+        self.count as f32 / (1.0 + age / RECENCY_SCALE)
+    }
+}
+
+/// 输入历史文件的内容
+#[derive(Serialize, Deserialize)]
+struct HistoryFile {
+    version: u64,
+    clock: u64,
+    selections: Vec<Selection>,
+}
+
+/// 用户输入历史：记录每次选择，并据此调整候选项的顺序
+///
+/// 时间用选择的序号表示，与系统时钟无关，因此排序结果是确定的。
+#[derive(Debug, Clone, Default)]
+pub struct UserHistory {
+    /// 输入 -> 该输入下的选择记录
+    selections: HashMap<String, Vec<Selection>>,
+    /// 已记录的选择总次数
+    clock: u64,
+    /// 持久化文件路径，None 表示只保存在内存中
+    path: Option<PathBuf>,
+}
+
+impl UserHistory {
+    /// 只保存在内存中的空历史
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 打开用户数据文件；文件不存在时从空历史开始，之后由 [`UserHistory::save`] 写入该文件
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let mut history = match fs::read_to_string(&path) {
+            Ok(content) => Self::import(&content)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Self::new(),
+            Err(e) => return Err(e),
+        };
+        history.path = Some(path);
+        Ok(history)
+    }
+
+    /// 从导出的 JSON 恢复历史
+    pub fn import(json_content: &str) -> io::Result<Self> {
+        let file: HistoryFile = serde_json::from_str(json_content)?;
+        if file.version != HISTORY_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("不支持的输入历史版本: {}", file.version),
+            ));
+        }
+
+        let mut history = Self::new();
+        history.clock = file.clock;
+        for selection in file.selections {
+            history.selections.entry(selection.input.clone()).or_default().push(selection);
+        }
+        Ok(history)
+    }
+
+    /// 导出为 JSON
+    pub fn export(&self) -> String {
+        let file = HistoryFile {
+            version: HISTORY_VERSION,
+            clock: self.clock,
+            selections: self.sorted_selections(),
+        };
+        serde_json::to_string_pretty(&file).unwrap_or_default()
+    }
+
+    /// 写入 open 时指定的文件：先写临时文件再替换，避免写到一半时损坏原文件
+    pub fn save(&self) -> io::Result<()> {
+        match &self.path {
+            Some(path) => self.save_to(path),
+            None => Ok(()),
+        }
+    }
+
+    /// 以原子方式写入指定文件
+    pub fn save_to(&self, path: &Path) -> io::Result<()> {
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+        let mut temp_name = path.as_os_str().to_owned();
+        temp_name.push(".tmp");
+        let temp_path = PathBuf::from(temp_name);
+        fs::write(&temp_path, self.export())?;
+        fs::rename(&temp_path, path)
+    }
+
+    /// 记录一次选择
+    pub fn record(&mut self, input: &str, candidate: &Candidate) {
+        if input.is_empty() {
+            return;
+        }
+        self.clock += 1;
+        let clock = self.clock;
+
+        let selections = self.selections.entry(input.to_string()).or_default();
+        match selections
+            .iter_mut()
+            .find(|s| s.text == candidate.text && s.pinyin_segments == candidate.pinyin_segments)
+        {
+            Some(selection) => {
+                selection.count += 1;
+                selection.last_used = clock;
+            }
+            None => selections.push(Selection {
+                input: input.to_string(),
+                text: candidate.text.clone(),
+                pinyin_segments: candidate.pinyin_segments.clone(),
+                count: 1,
+                last_used: clock,
+            }),
+        }
+
+        if self.len() > MAX_SELECTIONS {
+            self.evict_oldest();
+        }
+    }
+
+    /// 按历史调整候选项顺序：选择过的候选项的分数乘以 (1 + 权重)，
+    /// 再插到第一个分数比它低的候选项之前，其余候选项保持原有顺序
+    ///
+    /// 因此偶尔选错一次的纠错候选项不会一直排在分数高得多的精确匹配之前。
+    /// 选择过但不在列表中的候选项也会被加入，来源标记为 [`CandidateSource::History`]。
+    pub fn rerank(&self, input: &str, candidates: Vec<Candidate>) -> Vec<Candidate> {
+        let Some(selections) = self.selections.get(input) else {
+            return candidates;
+        };
+
+        let mut learned: Vec<Candidate> = Vec::new();
+        let mut ranked = Vec::new();
+        for mut candidate in candidates {
+            let selection = selections
+                .iter()
+                .find(|s| s.text == candidate.text && s.pinyin_segments == candidate.pinyin_segments);
+            match selection {
+                Some(selection) => {
+                    candidate.score *= 1.0 + selection.weight(self.clock);
+                    learned.push(candidate);
+                }
+                None => ranked.push(candidate),
+            }
+        }
+
+        for selection in selections {
+            let present = learned
+                .iter()
+                .any(|c| c.text == selection.text && c.pinyin_segments == selection.pinyin_segments);
+            if !present {
+                let kind = if selection.pinyin_segments.len() > 1 {
+                    CandidateKind::Phrase
+                } else {
+                    CandidateKind::Syllable
+                };
+                learned.push(Candidate::new(
+                    selection.text.clone(),
+                    selection.pinyin_segments.clone(),
+                    kind,
+                    HISTORY_SCORE * (1.0 + selection.weight(self.clock)),
+                    CandidateSource::History,
+                ));
+            }
+        }
+
+        // 分数相同时保持原有顺序（加入的候选项排在后面）
+        learned.sort_by(|a, b| b.score.total_cmp(&a.score));
+        for candidate in learned {
+            let position = ranked
+                .iter()
+                .position(|c| c.score < candidate.score)
+                .unwrap_or(ranked.len());
+            ranked.insert(position, candidate);
+        }
+        ranked.truncate(MAX_CANDIDATES);
+        ranked
+    }
+
+    /// 忘记某个输入下的某个选择，返回是否存在该记录
+    pub fn forget(&mut self, input: &str, text: &str) -> bool {
+        let Some(selections) = self.selections.get_mut(input) else {
+            return false;
+        };
+        let before = selections.len();
+        selections.retain(|s| s.text != text);
+        let removed = selections.len() != before;
+        if selections.is_empty() {
+            self.selections.remove(input);
+        }
+        removed
+    }
+
+    /// 忘记某个输入下的所有选择，返回是否存在记录
+    pub fn forget_input(&mut self, input: &str) -> bool {
+        self.selections.remove(input).is_some()
+    }
+
+    /// 清空所有记录（持久化文件要在 save 后才会更新）
+    pub fn reset(&mut self) {
+        self.selections.clear();
+        self.clock = 0;
+    }
+
+    /// 某个输入下的选择记录，按权重从高到低排列
+    pub fn selections_for(&self, input: &str) -> Vec<&Selection> {
+        let mut selections: Vec<&Selection> = self.selections.get(input).into_iter().flatten().collect();
+        selections.sort_by(|a, b| b.weight(self.clock).total_cmp(&a.weight(self.clock)));
+        selections
+    }
+
+    /// 已记录的选择总次数，用作时间
+    pub fn clock(&self) -> u64 {
+        self.clock
+    }
+
+    /// 选择记录的条数
+    pub fn len(&self) -> usize {
+        self.selections.values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.selections.is_empty()
+    }
+
+    /// 持久化文件路径
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// 所有记录，按输入和最近使用顺序排列，便于导出的文件保持稳定
+    fn sorted_selections(&self) -> Vec<Selection> {
+        let mut selections: Vec<Selection> = self.selections.values().flatten().cloned().collect();
+        selections.sort_by(|a, b| (&a.input, a.last_used, &a.text).cmp(&(&b.input, b.last_used, &b.text)));
+        selections
+    }
+
+    /// 淘汰最久未用的记录
+    fn evict_oldest(&mut self) {
+        let oldest = self
+            .selections
+            .values()
+            .flatten()
+            .min_by_key(|s| s.last_used)
+            .map(|s| (s.input.clone(), s.text.clone()));
+        if let Some((input, text)) = oldest {
+            self.forget(&input, &text);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(text: &str, score: f32, source: CandidateSource) -> Candidate {
+        Candidate::new(text, vec!["nuo".to_string()], CandidateKind::Syllable, score, source)
+    }
+
+    fn texts(candidates: &[Candidate]) -> Vec<&str> {
+        candidates.iter().map(|candidate| candidate.text.as_str()).collect()
+    }
+
+    #[test]
+    fn selection_moves_past_equal_scores() {
+        let mut history = UserHistory::new();
+        let candidates = vec![
+            candidate("ꆈ", 1.0, CandidateSource::Dictionary),
+            candidate("ꆇ", 1.0, CandidateSource::Dictionary),
+            candidate("ꆉ", 1.0, CandidateSource::Dictionary),
+        ];
+        history.record("nuo", &candidates[2]);
+        assert_eq!(texts(&history.rerank("nuo", candidates)), ["ꆉ", "ꆈ", "ꆇ"]);
+    }
+
+    #[test]
+    fn single_pick_does_not_outrank_much_better_candidate() {
+        let mut history = UserHistory::new();
+        let candidates = vec![
+            candidate("ꆈ", 1.0, CandidateSource::Dictionary),
+            candidate("ꆇ", 0.5, CandidateSource::Dictionary),
+            candidate("ꆉ", 0.3, CandidateSource::Correction),
+        ];
+        history.record("nuo", &candidates[2]);
+        // 选错一次的纠错候选项只前进到分数相近的位置
+        assert_eq!(texts(&history.rerank("nuo", candidates.clone())), ["ꆈ", "ꆉ", "ꆇ"]);
+
+        // 反复选择后排在最前面
+        for _ in 0..3 {
+            history.record("nuo", &candidates[2]);
+        }
+        assert_eq!(texts(&history.rerank("nuo", candidates)), ["ꆉ", "ꆈ", "ꆇ"]);
+    }
+
+    #[test]
+    fn missing_selection_is_added_from_history() {
+        let mut history = UserHistory::new();
+        history.record("nuo", &candidate("ꆈ", 1.0, CandidateSource::Dictionary));
+        let reranked = history.rerank("nuo", vec![candidate("ꆇ", 0.5, CandidateSource::Dictionary)]);
+        assert_eq!(texts(&reranked), ["ꆈ", "ꆇ"]);
+        assert_eq!(reranked[0].source, CandidateSource::History);
+        assert_eq!(texts(&history.rerank("nuosu", Vec::new())), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn export_and_import_round_trip() {
+        let mut history = UserHistory::new();
+        history.record("nuo", &candidate("ꆈ", 1.0, CandidateSource::Dictionary));
+        history.record("nuo", &candidate("ꆈ", 1.0, CandidateSource::Dictionary));
+        let imported = UserHistory::import(&history.export()).unwrap();
+        assert_eq!(imported.clock(), 2);
+        assert_eq!(imported.selections_for("nuo")[0].count, 2);
+    }
+}
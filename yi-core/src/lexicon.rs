@@ -0,0 +1,256 @@
+use crate::dictionary::{DictError, LoadMode, LoadReport};
+use crate::ime::YiIME;
+use crate::user_dict::split_pinyin;
+use serde::Deserialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 当前支持的最高词库格式版本
+pub const LEXICON_SCHEMA_VERSION: u64 = 1;
+
+/// 词库中的多音节词
+#[derive(Debug, Clone, PartialEq)]
+pub struct LexiconWord {
+    /// 彝文，每个字对应一个音节
+    pub text: String,
+    /// 拼音音节序列，例如 ["nuo", "su"]
+    pub pinyin: Vec<String>,
+    /// 使用频率
+    pub frequency: Option<u64>,
+    /// 释义
+    pub gloss: Option<String>,
+}
+
+impl LexiconWord {
+    /// 连续输入时的拼音，例如 "nuosu"
+    pub fn input(&self) -> String {
+        self.pinyin.concat()
+    }
+}
+
+/// 词库文件的顶层结构
+#[derive(Deserialize)]
+struct RawLexicon {
+    version: u64,
+    words: Vec<Value>,
+}
+
+/// 词库文件中的一个词
+#[derive(Deserialize)]
+struct RawWord {
+    text: String,
+    /// 音节之间用空格、短横线或撇号分隔
+    pinyin: String,
+    #[serde(default)]
+    frequency: Option<u64>,
+    #[serde(default)]
+    gloss: Option<String>,
+}
+
+/// 多音节词库：分词时优先匹配其中的词
+#[derive(Debug, Clone, Default)]
+pub struct Lexicon {
+    /// 按加载顺序排列的词
+    words: Vec<LexiconWord>,
+    /// 连续输入的拼音 -> 词在 words 中的下标
+    index: HashMap<String, Vec<usize>>,
+    /// 最长的词的连续输入长度（字符数）
+    max_len: usize,
+}
+
+impl Lexicon {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加词；彝文和拼音都相同的词已存在时返回 false
+    pub fn add(&mut self, word: LexiconWord) -> bool {
+        let input = word.input();
+        let exists = self
+            .lookup(&input)
+            .iter()
+            .any(|w| w.text == word.text && w.pinyin == word.pinyin);
+        if exists {
+            return false;
+        }
+        self.max_len = self.max_len.max(input.chars().count());
+        self.index.entry(input).or_default().push(self.words.len());
+        self.words.push(word);
+        true
+    }
+
+    /// 连续输入的拼音（如 "nuosu"）完整匹配的词，按加载顺序排列
+    pub fn lookup(&self, input: &str) -> Vec<&LexiconWord> {
+        self.index
+            .get(input)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.words[i])
+            .collect()
+    }
+
+    /// 拼音音节序列与 pinyin 完全相同的词
+    pub fn words_for(&self, pinyin: &[String]) -> Vec<&LexiconWord> {
+        self.lookup(&pinyin.concat())
+            .into_iter()
+            .filter(|word| word.pinyin == pinyin)
+            .collect()
+    }
+
+    /// 最长的词的连续输入长度（字符数），分词网格只在这个范围内查找词
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// 所有词，按加载顺序排列
+    pub fn words(&self) -> &[LexiconWord] {
+        &self.words
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+}
+
+impl YiIME {
+    /// 从JSON字符串加载词库（严格模式），需要先加载音节字典
+    ///
+    /// 格式为 `{"version": 1, "words": [{"text": "ꆈꌠ", "pinyin": "nuo su"}]}`，
+    /// 每个词还可以带 `frequency` 和 `gloss`。
+    pub fn load_lexicon_from_str(&mut self, json_content: &str) -> Result<(), DictError> {
+        self.load_lexicon_with_mode(json_content, LoadMode::Strict).map(|_| ())
+    }
+
+    /// 按指定模式加载词库，宽松模式下返回的报告中包含被跳过的词
+    pub fn load_lexicon_with_mode(&mut self, json_content: &str, mode: LoadMode) -> Result<LoadReport, DictError> {
+        let raw: RawLexicon = serde_json::from_str(json_content)?;
+        if !(1..=LEXICON_SCHEMA_VERSION).contains(&raw.version) {
+            return Err(DictError::UnknownSchemaVersion(raw.version.to_string()));
+        }
+
+        // 先检查所有词，全部通过后才修改词库，严格模式下失败时输入法保持原状
+        let mut report = LoadReport::default();
+        let mut accepted: Vec<LexiconWord> = Vec::new();
+        for value in raw.words {
+            let word = match serde_json::from_value::<RawWord>(value) {
+                Ok(raw) => self.lexicon_word(raw),
+                Err(e) => Err(DictError::InvalidEntry {
+                    key: "words".to_string(),
+                    message: format!("词必须是包含 text 和 pinyin 的对象: {}", e),
+                }),
+            };
+            let word = match word {
+                Ok(word) => word,
+                Err(e) => {
+                    report.check(mode, e)?;
+                    continue;
+                }
+            };
+
+            let duplicate = self.lexicon.words_for(&word.pinyin).iter().any(|w| w.text == word.text)
+                || accepted.iter().any(|w| w.text == word.text && w.pinyin == word.pinyin);
+            if duplicate {
+                report.check(mode, DictError::InvalidEntry { key: word.text, message: "词语重复出现".to_string() })?;
+                continue;
+            }
+            accepted.push(word);
+        }
+
+        for word in accepted {
+            if let Some(frequency) = word.frequency {
+                self.frequencies.add_phrase(&word.text, frequency);
+            }
+            self.lexicon.add(word);
+            report.loaded += 1;
+        }
+        Ok(report)
+    }
+
+    /// 检查词的每个字都有对应的读音
+    fn lexicon_word(&self, raw: RawWord) -> Result<LexiconWord, DictError> {
+        let invalid = |message: String| DictError::InvalidEntry { key: raw.text.clone(), message };
+
+        let pinyin = split_pinyin(&raw.pinyin);
+        let chars: Vec<char> = raw.text.chars().collect();
+        if pinyin.len() < 2 {
+            return Err(invalid("词库中的词至少包含两个音节".to_string()));
+        }
+        if chars.len() != pinyin.len() {
+            return Err(invalid(format!("{} 个字与 {} 个音节不对应", chars.len(), pinyin.len())));
+        }
+        for (ch, syllable) in chars.iter().zip(&pinyin) {
+            let readings = self.dictionary.get(ch.encode_utf8(&mut [0; 4]) as &str);
+            if !readings.is_some_and(|readings| readings.contains(syllable)) {
+                return Err(invalid(format!("字典中 {} 没有读音 {}", ch, syllable)));
+            }
+        }
+
+        Ok(LexiconWord {
+            text: raw.text,
+            pinyin,
+            frequency: raw.frequency,
+            gloss: raw.gloss.filter(|gloss| !gloss.trim().is_empty()),
+        })
+    }
+
+    /// 词的权重：由词语频率决定，没有频率时为 1
+    pub(crate) fn word_weight(&self, word: &LexiconWord) -> f32 {
+        self.phrase_weight(&word.text).unwrap_or(1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::candidates::{Candidate, CandidateSource};
+    use crate::language_model::{BigramModel, SENTENCE_START};
+
+    fn ime() -> YiIME {
+        YiIME::with_builtin_dictionaries().unwrap()
+    }
+
+    fn texts(candidates: &[Candidate]) -> Vec<&str> {
+        candidates.iter().map(|candidate| candidate.text.as_str()).collect()
+    }
+
+    #[test]
+    fn words_rank_first_in_segmentation() {
+        let ime = ime();
+        let results = ime.segment_pinyin("nuosu");
+        assert_eq!(results[0].segments, ["nuo", "su"]);
+        assert_eq!(results[0].words, [(0, 2)]);
+        assert!(results.iter().any(|result| result.segments == ["nu", "o", "su"]));
+    }
+
+    #[test]
+    fn whole_word_candidate() {
+        let ime = ime();
+        let candidates = ime.smart_convert("nuosu");
+        assert_eq!(candidates[0].text, "ꆈꌠ");
+        assert_eq!(candidates[0].source, CandidateSource::Lexicon);
+    }
+
+    #[test]
+    fn words_stay_first_with_language_model() {
+        // 模型中 nu-o-su 常见而 nuo 从未出现，逐字方案的分数高于词的方案
+        let mut model = BigramModel::new();
+        for (prev, syllable) in [(SENTENCE_START, "nu"), ("nu", "o"), ("o", "su")] {
+            model.add_unigram(syllable, 100);
+            model.add_bigram(prev, syllable, 100);
+        }
+        let mut ime = ime();
+        ime.set_language_model(model);
+
+        let results = ime.segment_pinyin("nuosu");
+        assert_eq!(results[0].words, [(0, 2)]);
+        let chars = results.iter().find(|result| result.segments == ["nu", "o", "su"]).unwrap();
+        assert!(chars.confidence > results[0].confidence);
+
+        assert_eq!(texts(&ime.smart_convert("nuosu"))[..2], ["ꆈꌠ", "ꆓꀑꌠ"]);
+        assert_eq!(ime.candidates("nuosu")[0].text, "ꆈꌠ");
+    }
+}
@@ -0,0 +1,40 @@
+pub mod ime;
+pub mod dictionary;
+pub mod segmentation;
+pub mod conversion;
+pub mod candidates;
+pub mod composer;
+pub mod trie;
+pub mod lattice;
+pub mod session;
+pub mod syllable;
+pub mod romanization;
+pub mod transliteration;
+pub mod options;
+pub mod toneless;
+pub mod correction;
+pub mod frequency;
+pub mod learning;
+pub mod user_dict;
+pub mod lexicon;
+pub mod language_model;
+
+pub use ime::{YiIME, ITERATION_MARK, YI_LEXICON, YI_RADICAL_DICT, YI_SYLLABLE_DICT};
+pub use dictionary::{DictEntry, DictError, DictionaryKind, LoadMode, LoadReport, ParsedDictionary, DICTIONARY_SCHEMA_VERSION};
+pub use options::{ImeOptions, Reduplication, DEFAULT_ITERATION_KEY};
+pub use correction::CorrectionOptions;
+pub use frequency::FrequencyTable;
+pub use learning::{Selection, UserHistory};
+pub use user_dict::{UserDictionary, UserPhrase};
+pub use lexicon::{Lexicon, LexiconWord, LEXICON_SCHEMA_VERSION};
+pub use language_model::{BigramModel, LanguageModel, BIGRAM_MODEL_VERSION, SENTENCE_START};
+pub use segmentation::{is_syllable_delimiter, SegmentResult, SYLLABLE_DELIMITERS};
+pub use conversion::{Conversions, YiCombinations};
+pub use candidates::{Candidate, CandidateKind, CandidateSource, MAX_CANDIDATES};
+pub use trie::PinyinTrie;
+pub use lattice::Lattice;
+pub use session::Session;
+pub use romanization::{is_yi_char, RomanToken, Romanization};
+pub use transliteration::{join_tokens, TextToken};
+pub use syllable::{Syllable, SyllableError, Tone};
+pub use composer::{Action, Composer, InputMode, Key, Modifiers};
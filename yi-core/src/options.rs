@@ -0,0 +1,38 @@
+use crate::correction::CorrectionOptions;
+
+/// 输入替字符号 ꀕ 的默认按键
+pub const DEFAULT_ITERATION_KEY: char = 'w';
+
+/// 连续两个相同的音节（如 bbubbu）对应的两种写法哪一种排在前面
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reduplication {
+    /// 重复字形式 ꁱꁱ 在前，替字符号形式 ꁱꀕ 作为备选紧随其后
+    #[default]
+    PreferRepeat,
+    /// 替字符号形式 ꁱꀕ 在前，重复字形式 ꁱꁱ 紧随其后
+    PreferMark,
+}
+
+/// 输入法引擎的可选行为，默认全部关闭
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImeOptions {
+    /// 无声调输入：不带声调标记的音节（如 ba）同时匹配 bat、bax、bap
+    pub toneless: bool,
+    /// 拼写纠错：为打错的音节提供带惩罚的候选，None 表示关闭
+    pub correction: Option<CorrectionOptions>,
+    /// 表示替字符号 ꀕ 的按键（小写字母或符号），可以出现在任意音节之后
+    pub iteration_key: char,
+    /// 连续输入两个相同音节时，替字符号形式和重复字形式的先后
+    pub reduplication: Reduplication,
+}
+
+impl Default for ImeOptions {
+    fn default() -> Self {
+        Self {
+            toneless: false,
+            correction: None,
+            iteration_key: DEFAULT_ITERATION_KEY,
+            reduplication: Reduplication::default(),
+        }
+    }
+}
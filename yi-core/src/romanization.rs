@@ -0,0 +1,191 @@
+use crate::ime::{YiIME, ITERATION_MARK};
+
+/// 罗马化结果中的一段
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RomanToken {
+    /// 彝文音节或部首及其读音（ꀕ 取前一音节的读音）
+    Yi { text: char, pinyin: String },
+    /// 原样保留的非彝文文本
+    Other(String),
+    /// 无法确定读音的彝文字符：字典中没有，或前面没有音节的 ꀕ
+    Unknown(char),
+}
+
+/// 彝文文本转写为拼音的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Romanization {
+    pub tokens: Vec<RomanToken>,
+}
+
+impl Romanization {
+    /// 无法确定读音的彝文字符及其在原文中的字符位置
+    pub fn unknown(&self) -> Vec<(usize, char)> {
+        let mut position = 0;
+        let mut unknown = Vec::new();
+        for token in &self.tokens {
+            match token {
+                RomanToken::Yi { .. } => position += 1,
+                RomanToken::Other(text) => position += text.chars().count(),
+                RomanToken::Unknown(ch) => {
+                    unknown.push((position, *ch));
+                    position += 1;
+                }
+            }
+        }
+        unknown
+    }
+
+    /// 是否所有彝文字符都找到了读音
+    pub fn is_complete(&self) -> bool {
+        !self.tokens.iter().any(|token| matches!(token, RomanToken::Unknown(_)))
+    }
+
+    /// 按顺序排列的彝文读音
+    pub fn pinyin_segments(&self) -> Vec<&str> {
+        self.tokens
+            .iter()
+            .filter_map(|token| match token {
+                RomanToken::Yi { pinyin, .. } => Some(pinyin.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// 拼音文本：相邻音节之间用空格分隔，非彝文文本和未知字符原样保留
+    pub fn to_pinyin(&self) -> String {
+        let mut result = String::new();
+        let mut previous_was_syllable = false;
+        for token in &self.tokens {
+            match token {
+                RomanToken::Yi { pinyin, .. } => {
+                    if previous_was_syllable {
+                        result.push(' ');
+                    }
+                    result.push_str(pinyin);
+                    previous_was_syllable = true;
+                }
+                RomanToken::Other(text) => {
+                    result.push_str(text);
+                    previous_was_syllable = false;
+                }
+                RomanToken::Unknown(ch) => {
+                    result.push(*ch);
+                    previous_was_syllable = false;
+                }
+            }
+        }
+        result
+    }
+}
+
+/// 是否为彝文字符（彝文音节区或彝文部首区）
+pub fn is_yi_char(ch: char) -> bool {
+    ('\u{A000}'..='\u{A4CF}').contains(&ch)
+}
+
+impl YiIME {
+    /// 将彝文文本转写为拼音
+    ///
+    /// 音节取字典中的第一个读音，部首取部首字典中的读音，ꀕ 重复前一音节的读音；
+    /// 非彝文文本原样保留，字典中没有的彝文字符记为未知。
+    /// ꀕ 只重复同一个词中紧挨着的音节，因此空格、标点等非彝文字符之后的 ꀕ 同样记为未知。
+    pub fn romanize(&self, text: &str) -> Romanization {
+        let mut tokens: Vec<RomanToken> = Vec::new();
+        let mut previous_reading: Option<String> = None;
+
+        for ch in text.chars() {
+            if !is_yi_char(ch) {
+                match tokens.last_mut() {
+                    Some(RomanToken::Other(other)) => other.push(ch),
+                    _ => tokens.push(RomanToken::Other(ch.to_string())),
+                }
+                previous_reading = None;
+                continue;
+            }
+
+            let reading = if ch == ITERATION_MARK {
+                previous_reading.clone()
+            } else {
+                self.reading_of(ch)
+            };
+
+            match reading {
+                Some(pinyin) => {
+                    previous_reading = Some(pinyin.clone());
+                    tokens.push(RomanToken::Yi { text: ch, pinyin });
+                }
+                None => {
+                    previous_reading = None;
+                    tokens.push(RomanToken::Unknown(ch));
+                }
+            }
+        }
+
+        Romanization { tokens }
+    }
+
+    /// 单个彝文音节或部首的主读音
+    fn reading_of(&self, ch: char) -> Option<String> {
+        let mut buffer = [0; 4];
+        let key: &str = ch.encode_utf8(&mut buffer);
+        self.dictionary
+            .get(key)
+            .and_then(|readings| readings.first())
+            .or_else(|| self.radical_dictionary.get(key))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ime() -> YiIME {
+        YiIME::with_builtin_dictionaries().unwrap()
+    }
+
+    #[test]
+    fn iteration_mark_repeats_previous_reading() {
+        let ime = ime();
+        let romanization = ime.romanize("ꆈꀕꌠ");
+        assert_eq!(romanization.pinyin_segments(), ["nuo", "nuo", "su"]);
+        assert_eq!(romanization.to_pinyin(), "nuo nuo su");
+        assert_eq!(ime.romanize("ꁮꀕꀕ").to_pinyin(), "bbu bbu bbu");
+        assert!(romanization.is_complete());
+    }
+
+    #[test]
+    fn radicals_use_radical_reading() {
+        let ime = ime();
+        assert_eq!(ime.romanize("꒖").pinyin_segments(), ["ggop"]);
+        assert_eq!(ime.romanize("꒖ꀕ").to_pinyin(), "ggop ggop");
+    }
+
+    #[test]
+    fn other_text_passes_through() {
+        let ime = ime();
+        let romanization = ime.romanize("ꆈꌠ, abc 12");
+        assert_eq!(romanization.tokens[2..], [RomanToken::Other(", abc 12".to_string())]);
+        assert_eq!(romanization.to_pinyin(), "nuo su, abc 12");
+    }
+
+    #[test]
+    fn unknown_positions() {
+        let ime = ime();
+        // U+A48D 位于彝文区块中但没有分配字符
+        let romanization = ime.romanize("ꀕꆈ a\u{A48D}ꌠ");
+        assert_eq!(romanization.unknown(), [(0, 'ꀕ'), (4, '\u{A48D}')]);
+        assert!(!romanization.is_complete());
+        assert_eq!(romanization.to_pinyin(), "ꀕnuo a\u{A48D}su");
+    }
+
+    #[test]
+    fn iteration_mark_does_not_cross_whitespace() {
+        let ime = ime();
+        let romanization = ime.romanize("ꆈꌠ ꀕ");
+        assert_eq!(romanization.unknown(), [(3, ITERATION_MARK)]);
+        assert_eq!(romanization.to_pinyin(), "nuo su ꀕ");
+        // 未知字符之后的 ꀕ 也没有可以重复的读音
+        assert_eq!(ime.romanize("\u{A48D}ꀕ").unknown().len(), 2);
+    }
+}
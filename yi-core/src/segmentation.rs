@@ -0,0 +1,199 @@
+use crate::ime::YiIME;
+use crate::lattice::Lattice;
+
+/// 分词结果
+#[derive(Debug, Clone)]
+pub struct SegmentResult {
+    /// 分词方案
+    pub segments: Vec<String>,
+    /// 对应的彝文字符
+    pub yi_chars: Vec<Vec<String>>,
+    /// 置信度分数
+    pub confidence: f32,
+    /// 是否包含纠错得到的音节（此时 segments 为纠正后的拼音）
+    pub corrected: bool,
+    /// 纠错的编辑代价之和，精确方案为 0
+    pub edit_cost: f32,
+    /// 词库中的词所覆盖的音节区间 [起始, 结束)
+    pub words: Vec<(usize, usize)>,
+    /// 替字符号 ꀕ 所在的音节序号，segments 中为它重复的前一个音节的读音
+    pub iterations: Vec<usize>,
+}
+
+/// 分词结果的最大数量
+pub const SEGMENT_LIMIT: usize = 10;
+
+/// 输入中强制分隔音节的字符：`bi'ap` 和 `bi-ap` 只能分为 bi 和 ap
+pub const SYLLABLE_DELIMITERS: [char; 2] = ['\'', '-'];
+
+/// 是否为强制分隔音节的字符
+pub fn is_syllable_delimiter(c: char) -> bool {
+    SYLLABLE_DELIMITERS.contains(&c)
+}
+
+/// 去掉输入中的音节分隔符，得到连续输入的拼音
+pub(crate) fn strip_delimiters(input: &str) -> String {
+    input.chars().filter(|&c| !is_syllable_delimiter(c)).collect()
+}
+
+/// 输入中的每个分隔符是否都位于 syllables 的音节之间（或输入的两端）
+pub(crate) fn respects_delimiters(input: &str, syllables: &[String]) -> bool {
+    let mut boundaries = vec![0];
+    for syllable in syllables {
+        boundaries.push(boundaries[boundaries.len() - 1] + syllable.chars().count());
+    }
+    let mut letters = 0;
+    for c in input.chars() {
+        if !is_syllable_delimiter(c) {
+            letters += 1;
+        } else if !boundaries.contains(&letters) {
+            return false;
+        }
+    }
+    true
+}
+
+impl YiIME {
+    /// 智能分词：处理有歧义的拼音序列
+    ///
+    /// 基于分词网格提取置信度最高的若干方案：词库中的词覆盖的输入越多越靠前，
+    /// 覆盖相同时按置信度从高到低排列。
+    /// 开启纠错时，纠错方案排在所有精确方案之后，编辑代价小的在前，代价相同时同样按词和置信度排列。
+    /// 与用户词语的拼音完全相同的方案排在最前面。
+    /// 输入中的 `'` 和 `-` 是强制的音节边界，任何方案都不会跨过它们；
+    /// 音节之后的替字符号按键（默认为 w）可以表示 ꀕ，重复前一个音节的读音。
+    pub fn segment_pinyin(&self, input: &str) -> Vec<SegmentResult> {
+        let mut results = Lattice::build(self, input).k_best(self, SEGMENT_LIMIT);
+        self.prefer_user_phrases(input, &mut results);
+        results
+    }
+
+    /// 计算音节的置信度（没有加载语言模型时使用）
+    pub(crate) fn calculate_segment_confidence(&self, segment: &str, length: usize) -> f32 {
+        let base_confidence = match length {
+            1 => 0.6,  // 单字符音节置信度较低
+            2 => 0.9,  // 双字符音节置信度高
+            3 => 0.8,  // 三字符音节置信度中等
+            _ => 0.7,  // 更长的音节置信度较低
+        };
+        
+        // 如果包含歧义字符，降低置信度
+        let has_ambiguous = segment.chars().any(Self::is_ambiguous_char);
+        if has_ambiguous {
+            base_confidence * 0.8
+        } else {
+            base_confidence
+        }
+    }
+
+    /// 计算歧义分割的置信度
+    pub(crate) fn calculate_ambiguous_confidence(&self, left: &str, right: &str) -> f32 {
+        let left_conf = self.calculate_segment_confidence(left, left.len());
+        let right_conf = self.calculate_segment_confidence(right, right.len());
+        (left_conf + right_conf) / 2.0 * 0.7 // 歧义分割总体置信度较低
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ime::ITERATION_MARK;
+
+    fn ime() -> YiIME {
+        YiIME::with_builtin_dictionaries().unwrap()
+    }
+
+    fn best(ime: &YiIME, input: &str) -> Vec<String> {
+        ime.segment_pinyin(input).remove(0).segments
+    }
+
+    #[test]
+    fn single_syllable() {
+        let ime = ime();
+        assert_eq!(best(&ime, "xiep"), ["xiep"]);
+    }
+
+    #[test]
+    fn tone_marker_before_initial() {
+        // t、x 既可以是声调也可以是声母，hxitbbu 只能分为 hxit 和 bbu
+        let ime = ime();
+        assert_eq!(best(&ime, "hxitbbu"), ["hxit", "bbu"]);
+        assert_eq!(best(&ime, "shyrxnuo"), ["shyrx", "nuo"]);
+    }
+
+    #[test]
+    fn results_are_sorted_and_limited() {
+        let ime = ime();
+        let results = ime.segment_pinyin("ayixiep");
+        assert!(!results.is_empty() && results.len() <= SEGMENT_LIMIT);
+        assert_eq!(results[0].segments, ["a", "yix", "iep"]);
+        for pair in results.windows(2) {
+            assert!(pair[0].confidence >= pair[1].confidence);
+        }
+    }
+
+    #[test]
+    fn delimiter_forces_boundary() {
+        let ime = ime();
+        assert_eq!(best(&ime, "bi'ap"), ["bi", "ap"]);
+        assert_eq!(best(&ime, "a''ba"), ["a", "ba"]);
+        assert_eq!(best(&ime, "a'"), ["a"]);
+    }
+
+    #[test]
+    fn delimiters_alone_are_not_input() {
+        let ime = ime();
+        for input in ["'", "--", "a''b"] {
+            assert!(ime.segment_pinyin(input).is_empty(), "{}", input);
+            assert!(ime.candidates(input).is_empty(), "{}", input);
+            assert!(ime.smart_convert(input).is_empty(), "{}", input);
+            assert!(!ime.is_valid_input_sequence(input), "{}", input);
+        }
+    }
+
+    #[test]
+    fn iteration_mark_repeats_previous_syllable() {
+        let ime = ime();
+        let results = ime.segment_pinyin("nuowsu");
+        assert_eq!(results[0].segments, ["nuo", "nuo", "su"]);
+        assert_eq!(results[0].iterations, [1]);
+        assert_eq!(results[0].yi_chars[1], [ITERATION_MARK.to_string()]);
+        // 连续的替字符号都重复同一个读音
+        assert_eq!(best(&ime, "nuoww"), ["nuo", "nuo", "nuo"]);
+    }
+
+    #[test]
+    fn iteration_mark_needs_previous_syllable() {
+        let ime = ime();
+        // 单独的 w 只是 wa 等音节的开头，分隔符之后的 w 前面也没有音节
+        assert!(ime.segment_pinyin("w").is_empty());
+        assert!(ime.segment_pinyin("'w").is_empty());
+        assert!(ime.is_valid_input_sequence("w"));
+        assert!(!ime.is_valid_input_sequence("'w"));
+        assert_eq!(best(&ime, "wa"), ["wa"]);
+        assert!(ime.segment_pinyin("wa")[0].iterations.is_empty());
+    }
+
+    #[test]
+    fn custom_iteration_key() {
+        let mut ime = ime();
+        ime.options.iteration_key = 'q';
+        assert_eq!(ime.segment_pinyin("nuoqsu")[0].iterations, [1]);
+        assert!(ime.segment_pinyin("nuowsu").is_empty());
+
+        // 不是字母的按键同样可以接在任意音节之后，但不能单独出现
+        ime.options.iteration_key = ';';
+        let results = ime.segment_pinyin("nuo;su");
+        assert_eq!(results[0].segments, ["nuo", "nuo", "su"]);
+        assert_eq!(results[0].iterations, [1]);
+        assert!(ime.segment_pinyin(";").is_empty());
+        assert!(ime.segment_pinyin("nuoqsu").is_empty());
+    }
+
+    #[test]
+    fn invalid_input_has_no_segmentation() {
+        let ime = ime();
+        assert!(ime.segment_pinyin("b").is_empty());
+        assert!(ime.segment_pinyin("").is_empty());
+    }
+}
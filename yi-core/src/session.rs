@@ -0,0 +1,89 @@
+use crate::ime::YiIME;
+use crate::lattice::Lattice;
+use crate::segmentation::{SegmentResult, SEGMENT_LIMIT};
+
+/// 一次输入过程中的分词状态
+///
+/// 追加字母时扩展上一次的分词网格，退格时截短网格，不必每次按键都从头分词。
+/// 合法性检查、候选项和智能转换都读取这里保存的同一份分词结果。
+#[derive(Debug, Clone)]
+pub struct Session {
+    input: String,
+    lattice: Lattice,
+    /// 整个输入的分词结果
+    segments: Vec<SegmentResult>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Self {
+            input: String::new(),
+            lattice: Lattice::new(SEGMENT_LIMIT),
+            segments: Vec::new(),
+        }
+    }
+
+    /// 由完整的输入建立会话
+    pub fn with_input(ime: &YiIME, input: &str) -> Self {
+        let mut session = Self::new();
+        for ch in input.chars() {
+            session.input.push(ch);
+            session.lattice.push(ime, ch);
+        }
+        session.update(ime);
+        session
+    }
+
+    /// 在输入末尾追加一个字符
+    pub fn push(&mut self, ime: &YiIME, ch: char) {
+        self.input.push(ch);
+        self.lattice.push(ime, ch);
+        self.update(ime);
+    }
+
+    /// 删除输入的最后一个字符
+    pub fn pop(&mut self, ime: &YiIME) -> Option<char> {
+        let ch = self.input.pop()?;
+        self.lattice.pop();
+        self.update(ime);
+        Some(ch)
+    }
+
+    /// 清空输入
+    pub fn clear(&mut self) {
+        self.input.clear();
+        self.lattice.clear();
+        self.segments.clear();
+    }
+
+    /// 当前输入的拼音
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.input.is_empty()
+    }
+
+    /// 当前输入的分词网格
+    pub fn lattice(&self) -> &Lattice {
+        &self.lattice
+    }
+
+    /// 整个输入的分词结果，与 [`YiIME::segment_pinyin`] 相同
+    pub fn segments(&self) -> &[SegmentResult] {
+        &self.segments
+    }
+
+    /// 由网格中已经计算好的路径重新提取分词结果
+    fn update(&mut self, ime: &YiIME) {
+        self.segments = self.lattice.k_best(ime, SEGMENT_LIMIT);
+        ime.prefer_user_phrases(&self.input, &mut self.segments);
+    }
+}
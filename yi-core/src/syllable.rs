@@ -0,0 +1,232 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// 声母表（凉山规范彝文拼音方案），按长度从长到短排列，便于最长匹配
+const INITIALS: [&str; 43] = [
+    "bb", "nb", "hm", "dd", "nd", "hn", "hl", "gg", "mg", "hx", "ng",
+    "zz", "nz", "ss", "zh", "ch", "rr", "nr", "sh", "jj", "nj", "ny",
+    "b", "p", "m", "f", "v", "d", "t", "n", "l", "g", "k", "h", "w",
+    "z", "c", "s", "r", "j", "q", "x", "y",
+];
+
+/// 韵母表
+const FINALS: [&str; 10] = ["i", "ie", "a", "uo", "o", "e", "u", "ur", "y", "yr"];
+
+/// 声调：用音节末尾的 t、x、p 标记，中平调不标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Tone {
+    /// 高平调（55），标记 t
+    High,
+    /// 次高调（44/34），标记 x
+    MidRising,
+    /// 中平调（33），不标
+    Mid,
+    /// 低降调（21），标记 p
+    LowFalling,
+}
+
+impl Tone {
+    /// 所有声调
+    pub const ALL: [Tone; 4] = [Tone::High, Tone::MidRising, Tone::Mid, Tone::LowFalling];
+
+    /// 声调标记字母，中平调没有标记
+    pub fn marker(&self) -> Option<char> {
+        match self {
+            Tone::High => Some('t'),
+            Tone::MidRising => Some('x'),
+            Tone::Mid => None,
+            Tone::LowFalling => Some('p'),
+        }
+    }
+
+    /// 由声调标记字母得到声调
+    pub fn from_marker(marker: char) -> Option<Tone> {
+        match marker {
+            't' => Some(Tone::High),
+            'x' => Some(Tone::MidRising),
+            'p' => Some(Tone::LowFalling),
+            _ => None,
+        }
+    }
+}
+
+/// 音节解析错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyllableError {
+    /// 空字符串
+    Empty,
+    /// 无法拆分为声母 + 韵母 + 声调
+    Invalid(String),
+}
+
+impl fmt::Display for SyllableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyllableError::Empty => write!(f, "音节为空"),
+            SyllableError::Invalid(text) => write!(f, "{} 不是合法的彝文拼音音节", text),
+        }
+    }
+}
+
+impl std::error::Error for SyllableError {}
+
+/// 彝文拼音音节：声母 + 韵母 + 声调
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Syllable {
+    initial: &'static str,
+    final_: &'static str,
+    tone: Tone,
+}
+
+impl Syllable {
+    /// 由各组成部分构建音节，声母或韵母不在表中时返回 None
+    pub fn new(initial: &str, final_: &str, tone: Tone) -> Option<Self> {
+        let initial = if initial.is_empty() {
+            ""
+        } else {
+            INITIALS.iter().copied().find(|i| *i == initial)?
+        };
+        let final_ = FINALS.iter().copied().find(|f| *f == final_)?;
+        Some(Self { initial, final_, tone })
+    }
+
+    /// 解析拼音音节，例如 "bbiep" -> bb + ie + 低降调
+    pub fn parse(text: &str) -> Result<Self, SyllableError> {
+        if text.is_empty() {
+            return Err(SyllableError::Empty);
+        }
+        let invalid = || SyllableError::Invalid(text.to_string());
+
+        // 韵母都是元音，因此末尾的 t、x、p 一定是声调标记
+        let (body, tone) = match text.chars().last().and_then(Tone::from_marker) {
+            Some(tone) if text.len() > 1 => (&text[..text.len() - 1], tone),
+            _ => (text, Tone::Mid),
+        };
+
+        // 声母取最长匹配，剩余部分必须是韵母；没有声母的音节放在最后尝试
+        INITIALS
+            .iter()
+            .copied()
+            .chain(std::iter::once(""))
+            .filter_map(|initial| {
+                let rest = body.strip_prefix(initial)?;
+                let final_ = FINALS.iter().copied().find(|f| *f == rest)?;
+                Some(Self { initial, final_, tone })
+            })
+            .next()
+            .ok_or_else(invalid)
+    }
+
+    /// 声母，零声母音节为空字符串
+    pub fn initial(&self) -> &'static str {
+        self.initial
+    }
+
+    /// 韵母
+    pub fn final_(&self) -> &'static str {
+        self.final_
+    }
+
+    pub fn tone(&self) -> Tone {
+        self.tone
+    }
+
+    /// 声调不同、声母韵母相同的音节
+    pub fn with_tone(&self, tone: Tone) -> Self {
+        Self { tone, ..*self }
+    }
+
+    /// 不带声调标记的拼音，例如 "bbie"
+    pub fn toneless(&self) -> String {
+        format!("{}{}", self.initial, self.final_)
+    }
+
+    /// 还原为拼音字符串
+    pub fn to_pinyin(&self) -> String {
+        let mut pinyin = self.toneless();
+        if let Some(marker) = self.tone.marker() {
+            pinyin.push(marker);
+        }
+        pinyin
+    }
+}
+
+impl fmt::Display for Syllable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_pinyin())
+    }
+}
+
+impl FromStr for Syllable {
+    type Err = SyllableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ime::YiIME;
+
+    fn parts(text: &str) -> (&'static str, &'static str, Tone) {
+        let syllable = Syllable::parse(text).unwrap();
+        (syllable.initial(), syllable.final_(), syllable.tone())
+    }
+
+    #[test]
+    fn four_tones() {
+        assert_eq!(parts("bbit"), ("bb", "i", Tone::High));
+        assert_eq!(parts("bbix"), ("bb", "i", Tone::MidRising));
+        assert_eq!(parts("bbi"), ("bb", "i", Tone::Mid));
+        assert_eq!(parts("bbip"), ("bb", "i", Tone::LowFalling));
+        for tone in Tone::ALL {
+            assert_eq!(tone.marker().and_then(Tone::from_marker).unwrap_or(Tone::Mid), tone);
+        }
+    }
+
+    #[test]
+    fn nasal_and_aspirated_initials() {
+        assert_eq!(parts("nbap"), ("nb", "a", Tone::LowFalling));
+        assert_eq!(parts("mgo"), ("mg", "o", Tone::Mid));
+        assert_eq!(parts("hxop"), ("hx", "o", Tone::LowFalling));
+        assert_eq!(parts("hmo"), ("hm", "o", Tone::Mid));
+        assert_eq!(parts("nyip"), ("ny", "i", Tone::LowFalling));
+        // 最长匹配：hxit 不是 h + xi
+        assert_eq!(parts("hxit"), ("hx", "i", Tone::High));
+        assert_eq!(parts("shyrx"), ("sh", "yr", Tone::MidRising));
+        assert_eq!(parts("iep"), ("", "ie", Tone::LowFalling));
+    }
+
+    #[test]
+    fn every_dictionary_reading_round_trips() {
+        let ime = YiIME::with_builtin_dictionaries().unwrap();
+        for pinyin in ime.syllable_set.iter().chain(ime.radical_pinyin_index.keys()) {
+            let syllable: Syllable = pinyin.parse().unwrap();
+            assert_eq!(syllable.to_pinyin(), *pinyin);
+            assert_eq!(syllable.to_string(), *pinyin);
+            assert_eq!(ime.parse_syllable(pinyin), Some(syllable));
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_syllables() {
+        assert_eq!(Syllable::parse(""), Err(SyllableError::Empty));
+        for text in ["w", "p", "bb", "xyz", "nuosu", "a1", "ꀀ"] {
+            assert_eq!(Syllable::parse(text), Err(SyllableError::Invalid(text.to_string())), "{}", text);
+        }
+        assert_eq!(SyllableError::Invalid("w".to_string()).to_string(), "w 不是合法的彝文拼音音节");
+    }
+
+    #[test]
+    fn builds_from_parts() {
+        let syllable = Syllable::new("hx", "o", Tone::LowFalling).unwrap();
+        assert_eq!(syllable.to_pinyin(), "hxop");
+        assert_eq!(syllable.toneless(), "hxo");
+        assert_eq!(syllable.with_tone(Tone::High).to_pinyin(), "hxot");
+        assert_eq!(Syllable::new("", "a", Tone::Mid).unwrap().to_pinyin(), "a");
+        assert!(Syllable::new("xx", "a", Tone::Mid).is_none());
+        assert!(Syllable::new("b", "ao", Tone::Mid).is_none());
+    }
+}
@@ -0,0 +1,105 @@
+use crate::ime::YiIME;
+use crate::syllable::Syllable;
+
+/// 无声调匹配（声调与输入不同）的字的权重
+pub(crate) const TONELESS_WEIGHT: f32 = 0.9;
+
+/// 只能通过无声调匹配得到的音节在分词时的置信度系数
+pub(crate) const TONELESS_PENALTY: f32 = 0.9;
+
+impl YiIME {
+    /// 把字典中的拼音加入无声调索引
+    pub(crate) fn index_toneless(&mut self, pinyin: &str) {
+        let Ok(syllable) = Syllable::parse(pinyin) else {
+            return;
+        };
+        let toneless = syllable.toneless();
+        let readings = self.toneless_index.entry(toneless.clone()).or_default();
+        if !readings.iter().any(|reading| reading == pinyin) {
+            readings.push(pinyin.to_string());
+        }
+        self.toneless_trie.insert(&toneless);
+    }
+
+    /// 输入的音节是否能匹配字典中的音节（无声调输入时包括其他声调）
+    pub fn is_complete_syllable(&self, pinyin: &str) -> bool {
+        self.syllable_set.contains(pinyin)
+            || (self.options.toneless && self.toneless_index.contains_key(pinyin))
+    }
+
+    /// 输入的音节实际可能对应的字典拼音
+    ///
+    /// 声调完全匹配的排在最前面；无声调输入时其余声调按使用频率从高到低、
+    /// 再按声调顺序排列。
+    pub fn segment_readings(&self, segment: &str) -> Vec<String> {
+        let mut readings = Vec::new();
+        if self.syllable_set.contains(segment) {
+            readings.push(segment.to_string());
+        }
+        if !self.options.toneless {
+            return readings;
+        }
+
+        let mut variants: Vec<&String> = self
+            .toneless_index
+            .get(segment)
+            .into_iter()
+            .flatten()
+            .filter(|reading| reading.as_str() != segment)
+            .collect();
+        variants.sort_by_key(|reading| {
+            let tone = Syllable::parse(reading).map(|s| s.tone()).ok();
+            (std::cmp::Reverse(self.reading_frequency(reading)), tone, reading.as_str())
+        });
+        readings.extend(variants.into_iter().cloned());
+        readings
+    }
+
+    /// 输入的音节对应的所有彝文字符，顺序与 [`YiIME::segment_readings`] 一致
+    pub fn segment_chars(&self, segment: &str) -> Vec<String> {
+        let mut chars: Vec<String> = Vec::new();
+        for reading in self.segment_readings(segment) {
+            for yi_char in self.pinyin_index.get(&reading).into_iter().flatten() {
+                if !chars.contains(yi_char) {
+                    chars.push(yi_char.clone());
+                }
+            }
+        }
+        chars
+    }
+
+    /// 彝文字符在输入的音节下的实际读音（无声调输入时可能与输入不同）
+    pub fn reading_for(&self, yi_char: &str, segment: &str) -> Option<String> {
+        let readings = self.dictionary.get(yi_char)?;
+        if readings.iter().any(|reading| reading == segment) {
+            return Some(segment.to_string());
+        }
+        if !self.options.toneless {
+            return None;
+        }
+        readings
+            .iter()
+            .find(|reading| Syllable::parse(reading).is_ok_and(|s| s.toneless() == segment))
+            .cloned()
+    }
+
+    /// 分词时音节的置信度系数：只能通过无声调匹配得到的音节置信度较低
+    pub(crate) fn toneless_penalty(&self, segment: &str) -> f32 {
+        if self.syllable_set.contains(segment) {
+            1.0
+        } else {
+            TONELESS_PENALTY
+        }
+    }
+
+    /// 拼音对应的字中最高的使用频率
+    fn reading_frequency(&self, reading: &str) -> u64 {
+        self.pinyin_index
+            .get(reading)
+            .into_iter()
+            .flatten()
+            .filter_map(|yi_char| self.entries.get(yi_char)?.frequency)
+            .max()
+            .unwrap_or(0)
+    }
+}
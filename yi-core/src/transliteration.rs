@@ -0,0 +1,228 @@
+use crate::composer::punctuation_for;
+use crate::ime::YiIME;
+use crate::segmentation::is_syllable_delimiter;
+
+/// 批量转写时文本被切分成的单元
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TextToken {
+    /// 转写成功的拼音词
+    Yi { pinyin: String, text: String },
+    /// 映射为全角形式的标点
+    Punctuation { source: char, text: &'static str },
+    /// 空白
+    Space(String),
+    /// 原样保留的内容：数字、拉丁词、无法转写的拼音和其他字符
+    Other(String),
+}
+
+impl TextToken {
+    /// 在输出中占据的文本
+    pub fn text(&self) -> &str {
+        match self {
+            TextToken::Yi { text, .. } => text,
+            TextToken::Punctuation { text, .. } => text,
+            TextToken::Space(text) | TextToken::Other(text) => text,
+        }
+    }
+
+    /// 是否为彝文或全角标点：这类单元之间不需要空格和连字符
+    fn is_yi_script(&self) -> bool {
+        matches!(self, TextToken::Yi { .. } | TextToken::Punctuation { .. })
+    }
+}
+
+/// 半角标点对应的按键及 Shift 状态，与输入时的标点表保持一致
+fn punctuation_key(ch: char) -> Option<(char, bool)> {
+    let key = match ch {
+        '[' | ']' | '\\' | ';' | ',' | '.' => (ch, false),
+        '{' => ('[', true),
+        '}' => (']', true),
+        '|' => ('\\', true),
+        ':' => (';', true),
+        '<' => (',', true),
+        '>' => ('.', true),
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// 将转写单元拼接为输出文本
+///
+/// 彝文和全角标点之间的空格（换行除外）和连字符被去掉，其余单元原样拼接。
+pub fn join_tokens(tokens: &[TextToken]) -> String {
+    let mut output = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        let joins_yi = || {
+            let before = tokens[..i].iter().rev().find(|t| !matches!(t, TextToken::Space(_)));
+            let after = tokens[i + 1..].iter().find(|t| !matches!(t, TextToken::Space(_)));
+            before.is_some_and(TextToken::is_yi_script) && after.is_some_and(TextToken::is_yi_script)
+        };
+        let skip = match token {
+            TextToken::Space(space) => !space.contains('\n') && joins_yi(),
+            TextToken::Other(other) => other == "-" && joins_yi(),
+            _ => false,
+        };
+        if !skip {
+            output.push_str(token.text());
+        }
+    }
+    output
+}
+
+impl YiIME {
+    /// 将整段拼音文本转写为彝文
+    ///
+    /// 每个拼音词取最佳分词和最高分的组合，词内的 `-` 和 `'` 是强制的音节边界；
+    /// 标点按输入法的标点表转换；彝文之间的空格和连字符被去掉，
+    /// 数字、拉丁词以及无法转写的词原样保留。
+    pub fn transliterate_text(&self, text: &str) -> String {
+        join_tokens(&self.transliterate_tokens(text))
+    }
+
+    /// 将拼音文本切分并逐个转写，保留每个单元的来源以便调用者检查未转写的部分
+    pub fn transliterate_tokens(&self, text: &str) -> Vec<TextToken> {
+        let chars: Vec<char> = text.chars().collect();
+        let mut tokens: Vec<TextToken> = Vec::new();
+        let mut i = 0;
+        // 替字符号按键不是字母时也属于拼音词
+        let iteration_key = self.options.iteration_key;
+        let is_word_char = |c: &char| c.is_ascii_alphabetic() || *c == iteration_key;
+
+        while i < chars.len() {
+            let ch = chars[i];
+            let run_end = |pred: fn(&char) -> bool| {
+                i + chars[i..].iter().take_while(|c| pred(c)).count()
+            };
+
+            if is_word_char(&ch) {
+                // 字母之间单个的音节分隔符（bi-ap、bi'ap）与输入时一样是强制的音节边界
+                let mut end = i + chars[i..].iter().take_while(|c| is_word_char(c)).count();
+                while chars.get(end).is_some_and(|&c| is_syllable_delimiter(c))
+                    && chars.get(end + 1).is_some_and(is_word_char)
+                {
+                    end += 1 + chars[end + 1..].iter().take_while(|c| is_word_char(c)).count();
+                }
+                let word: String = chars[i..end].iter().collect();
+                match self.transliterate_word(&word) {
+                    Some(yi) => tokens.push(TextToken::Yi { pinyin: word, text: yi }),
+                    // 整体无法转写时在分隔符处拆开，各段分别转写
+                    None => {
+                        let mut rest = word.as_str();
+                        while let Some(position) = rest.find(is_syllable_delimiter) {
+                            tokens.push(self.word_token(&rest[..position]));
+                            tokens.push(TextToken::Other(rest[position..position + 1].to_string()));
+                            rest = &rest[position + 1..];
+                        }
+                        tokens.push(self.word_token(rest));
+                    }
+                }
+                i = end;
+            } else if ch.is_whitespace() {
+                let end = run_end(|c| c.is_whitespace());
+                tokens.push(TextToken::Space(chars[i..end].iter().collect()));
+                i = end;
+            } else {
+                // 数字之间的标点（如 3.14、1,000）属于数字本身
+                let between_digits = i > 0
+                    && chars[i - 1].is_ascii_digit()
+                    && chars.get(i + 1).is_some_and(char::is_ascii_digit);
+                let punctuation = punctuation_key(ch)
+                    .filter(|_| !between_digits)
+                    .and_then(|(key, shift)| punctuation_for(key, shift));
+                match punctuation {
+                    Some(text) => tokens.push(TextToken::Punctuation { source: ch, text }),
+                    None => match tokens.last_mut() {
+                        // 连续的其他字符合并，连字符单独成一个单元以便在彝文词之间去掉
+                        Some(TextToken::Other(other))
+                            if ch != '-' && other != "-" && !other.ends_with(|c: char| c.is_ascii_alphabetic()) =>
+                        {
+                            other.push(ch)
+                        }
+                        _ => tokens.push(TextToken::Other(ch.to_string())),
+                    },
+                }
+                i += 1;
+            }
+        }
+
+        tokens
+    }
+
+    /// 不含分隔符的拼音词对应的单元，无法转写时原样保留
+    fn word_token(&self, word: &str) -> TextToken {
+        match self.transliterate_word(word) {
+            Some(yi) => TextToken::Yi { pinyin: word.to_string(), text: yi },
+            None => TextToken::Other(word.to_string()),
+        }
+    }
+
+    /// 转写单个拼音词；只有首字母大写的词按小写处理，其他位置有大写字母的词视为拉丁词
+    fn transliterate_word(&self, word: &str) -> Option<String> {
+        if word.chars().skip(1).any(|c| c.is_ascii_uppercase()) {
+            return None;
+        }
+        let word = word.to_ascii_lowercase();
+
+        // 用户词库中的词语优先
+        if let Some(phrase) = self.user_dictionary.lookup(&word).first() {
+            return Some(phrase.text.clone());
+        }
+
+        // 与输入时相同，音节之后的替字符号按键（默认为 w）可以表示 ꀕ
+        let best = self.segment_pinyin(&word).into_iter().next()?;
+        self.best_conversion(&best)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ime() -> YiIME {
+        YiIME::with_builtin_dictionaries().unwrap()
+    }
+
+    #[test]
+    fn punctuation_follows_the_key_table() {
+        let ime = ime();
+        assert_eq!(ime.transliterate_text("nuo su, bbur-ma. hxit"), "ꆈꌠ，ꁱꂷ。ꉆ");
+        // Shift 组合的标点
+        assert_eq!(ime.transliterate_text("a<b>"), "ꀊ《b》");
+        assert_eq!(
+            ime.transliterate_tokens("a:"),
+            vec![
+                TextToken::Yi { pinyin: "a".into(), text: "ꀊ".into() },
+                TextToken::Punctuation { source: ':', text: "：" },
+            ]
+        );
+    }
+
+    #[test]
+    fn numbers_and_latin_words_pass_through() {
+        let ime = ime();
+        assert_eq!(ime.transliterate_text("nuosu-hxop 3.14 hello"), "ꆈꌠꉙ 3.14 hello");
+        assert_eq!(ime.transliterate_text("iPhone nuo"), "iPhone ꆈ");
+        let tokens = ime.transliterate_tokens("3.14 iPhone");
+        assert_eq!(tokens[0], TextToken::Other("3.14".into()));
+        assert_eq!(tokens[2], TextToken::Other("iPhone".into()));
+    }
+
+    #[test]
+    fn spaces_and_hyphens_join_yi_words() {
+        let ime = ime();
+        assert_eq!(ime.transliterate_text("nuo su"), "ꆈꌠ");
+        assert_eq!(ime.transliterate_text("nuo-su"), "ꆈꌠ");
+        // 换行保留
+        assert_eq!(ime.transliterate_text("nuo\nsu"), "ꆈ\nꌠ");
+        // 与拉丁词相邻的空格和连字符保留
+        assert_eq!(ime.transliterate_text("nuo su-hello"), "ꆈꌠ-hello");
+        assert_eq!(ime.transliterate_text("hello nuo"), "hello ꆈ");
+    }
+
+    #[test]
+    fn join_matches_transliterate_text() {
+        let ime = ime();
+        let text = "nuo su, 3.14 - hello\nbbur-ma";
+        assert_eq!(join_tokens(&ime.transliterate_tokens(text)), ime.transliterate_text(text));
+    }
+}
@@ -0,0 +1,168 @@
+/// 拼音前缀树：在加载字典时构建，用于前缀查询和分词时的音节匹配
+#[derive(Debug, Clone)]
+pub struct PinyinTrie {
+    nodes: Vec<TrieNode>,
+    /// 最长的拼音长度（字符数）
+    max_len: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    /// 子节点，按字符排序，便于二分查找和按字典序遍历
+    children: Vec<(char, usize)>,
+    /// 是否为一个完整拼音的结尾
+    terminal: bool,
+}
+
+impl PinyinTrie {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![TrieNode::default()],
+            max_len: 0,
+        }
+    }
+
+    /// 插入一个拼音
+    pub fn insert(&mut self, pinyin: &str) {
+        let mut node = 0;
+        let mut len = 0;
+        for ch in pinyin.chars() {
+            node = match self.child(node, ch) {
+                Ok(next) => next,
+                Err(pos) => {
+                    let next = self.nodes.len();
+                    self.nodes.push(TrieNode::default());
+                    self.nodes[node].children.insert(pos, (ch, next));
+                    next
+                }
+            };
+            len += 1;
+        }
+        self.nodes[node].terminal = true;
+        self.max_len = self.max_len.max(len);
+    }
+
+    /// 是否包含该完整拼音
+    pub fn contains(&self, pinyin: &str) -> bool {
+        self.find(pinyin).is_some_and(|node| self.nodes[node].terminal)
+    }
+
+    /// 是否存在以 prefix 开头的拼音（prefix 本身也算）
+    pub fn is_prefix(&self, prefix: &str) -> bool {
+        self.find(prefix).is_some_and(|node| self.has_terminal(node))
+    }
+
+    /// 以 prefix 开头的所有拼音，按字典序排列
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        let mut results = Vec::new();
+        if let Some(node) = self.find(prefix) {
+            let mut current = prefix.to_string();
+            self.collect(node, &mut current, &mut results);
+        }
+        results
+    }
+
+    /// 最长的拼音长度（字符数）
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.nodes[0].terminal && self.nodes[0].children.is_empty()
+    }
+
+    fn child(&self, node: usize, ch: char) -> Result<usize, usize> {
+        let children = &self.nodes[node].children;
+        children
+            .binary_search_by(|(c, _)| c.cmp(&ch))
+            .map(|pos| children[pos].1)
+    }
+
+    fn find(&self, prefix: &str) -> Option<usize> {
+        let mut node = 0;
+        for ch in prefix.chars() {
+            node = self.child(node, ch).ok()?;
+        }
+        Some(node)
+    }
+
+    fn has_terminal(&self, node: usize) -> bool {
+        // 只有根节点可能没有后代终点（空树），其余节点都由插入产生
+        node != 0 || !self.is_empty()
+    }
+
+    fn collect(&self, node: usize, current: &mut String, results: &mut Vec<String>) {
+        if self.nodes[node].terminal {
+            results.push(current.clone());
+        }
+        for &(ch, child) in &self.nodes[node].children {
+            current.push(ch);
+            self.collect(child, current, results);
+            current.pop();
+        }
+    }
+}
+
+impl Default for PinyinTrie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trie(words: &[&str]) -> PinyinTrie {
+        let mut trie = PinyinTrie::new();
+        for word in words {
+            trie.insert(word);
+        }
+        trie
+    }
+
+    #[test]
+    fn contains_only_whole_pinyin() {
+        let trie = trie(&["nuo", "nuop", "su"]);
+        assert!(trie.contains("nuo"));
+        assert!(trie.contains("nuop"));
+        assert!(trie.contains("su"));
+        assert!(!trie.contains("nu"));
+        assert!(!trie.contains("nuox"));
+        assert!(!trie.contains(""));
+    }
+
+    #[test]
+    fn prefixes() {
+        let trie = trie(&["nuo", "su"]);
+        assert!(trie.is_prefix("n"));
+        assert!(trie.is_prefix("nuo"));
+        assert!(trie.is_prefix(""));
+        assert!(!trie.is_prefix("nuop"));
+        assert!(!trie.is_prefix("x"));
+        assert!(!PinyinTrie::new().is_prefix(""));
+    }
+
+    #[test]
+    fn completions_are_sorted() {
+        let trie = trie(&["sut", "su", "sup", "nuo", "sux"]);
+        assert_eq!(trie.completions("su"), vec!["su", "sup", "sut", "sux"]);
+        assert_eq!(trie.completions("n"), vec!["nuo"]);
+        assert!(trie.completions("x").is_empty());
+        assert_eq!(trie.completions("").len(), 5);
+    }
+
+    #[test]
+    fn length_and_emptiness() {
+        let mut trie = PinyinTrie::new();
+        assert!(trie.is_empty());
+        assert_eq!(trie.max_len(), 0);
+        trie.insert("hxop");
+        trie.insert("a");
+        // 重复插入不改变结果
+        trie.insert("a");
+        assert!(!trie.is_empty());
+        assert_eq!(trie.max_len(), 4);
+        assert_eq!(trie.completions(""), vec!["a", "hxop"]);
+    }
+}
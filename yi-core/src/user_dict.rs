@@ -0,0 +1,349 @@
+use crate::candidates::{Candidate, CandidateKind, CandidateSource};
+use crate::dictionary::{DictError, LoadMode, LoadReport};
+use crate::ime::YiIME;
+use crate::segmentation::{is_syllable_delimiter, respects_delimiters, strip_delimiters, SegmentResult};
+use crate::syllable::Syllable;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// 用户词库导出文件的格式版本
+const USER_DICT_VERSION: u64 = 1;
+
+/// 用户词语：拼音序列 -> 彝文
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserPhrase {
+    /// 拼音音节序列，例如 ["nuo", "su"]
+    pub pinyin: Vec<String>,
+    /// 彝文
+    pub text: String,
+    /// 释义
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gloss: Option<String>,
+}
+
+impl UserPhrase {
+    /// 由拼音文本构建词语，拼音可以用空格、短横线或撇号分隔音节
+    pub fn new(pinyin: &str, text: &str, gloss: Option<&str>) -> Result<Self, DictError> {
+        let invalid = |message: String| DictError::InvalidEntry { key: text.to_string(), message };
+
+        let syllables = split_pinyin(pinyin);
+        if syllables.is_empty() {
+            return Err(invalid("拼音为空".to_string()));
+        }
+        if let Some(bad) = syllables.iter().find(|s| Syllable::parse(s).is_err()) {
+            return Err(invalid(format!("{} 不是合法的彝文拼音音节", bad)));
+        }
+        if text.trim().is_empty() {
+            return Err(DictError::EmptyReadings(pinyin.to_string()));
+        }
+
+        Ok(Self {
+            pinyin: syllables,
+            text: text.trim().to_string(),
+            gloss: gloss.map(str::trim).filter(|g| !g.is_empty()).map(str::to_string),
+        })
+    }
+
+    /// 连续输入时的拼音，例如 "nuosu"
+    pub fn input(&self) -> String {
+        self.pinyin.concat()
+    }
+}
+
+/// 按空格、短横线或撇号拆分拼音音节
+pub(crate) fn split_pinyin(pinyin: &str) -> Vec<String> {
+    pinyin
+        .split(|c: char| c.is_whitespace() || is_syllable_delimiter(c))
+        .filter(|s| !s.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// 用户词库的导出格式
+#[derive(Serialize, Deserialize)]
+struct UserDictFile {
+    version: u64,
+    phrases: Vec<UserPhrase>,
+}
+
+/// 用户词库：完整输入与词语的拼音相同时，该词语优先于分词结果
+#[derive(Debug, Clone, Default)]
+pub struct UserDictionary {
+    /// 按添加顺序排列的词语
+    phrases: Vec<UserPhrase>,
+    /// 连续输入的拼音 -> 词语在 phrases 中的下标
+    index: HashMap<String, Vec<usize>>,
+}
+
+impl UserDictionary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 添加词语；拼音和彝文都相同的词语已存在时只更新释义，返回是否新增
+    pub fn add(&mut self, phrase: UserPhrase) -> bool {
+        let input = phrase.input();
+        let existing = self.index.get(&input).into_iter().flatten().copied().find(|&i| {
+            self.phrases[i].pinyin == phrase.pinyin && self.phrases[i].text == phrase.text
+        });
+        match existing {
+            Some(i) => {
+                self.phrases[i].gloss = phrase.gloss;
+                false
+            }
+            None => {
+                self.index.entry(input).or_default().push(self.phrases.len());
+                self.phrases.push(phrase);
+                true
+            }
+        }
+    }
+
+    /// 删除拼音为 pinyin（可带分隔符）、彝文为 text 的词语，返回是否存在
+    pub fn remove(&mut self, pinyin: &str, text: &str) -> bool {
+        let syllables = split_pinyin(pinyin);
+        let before = self.phrases.len();
+        self.phrases.retain(|p| !(p.pinyin == syllables && p.text == text));
+        let removed = self.phrases.len() != before;
+        if removed {
+            self.rebuild_index();
+        }
+        removed
+    }
+
+    /// 清空词库
+    pub fn clear(&mut self) {
+        self.phrases.clear();
+        self.index.clear();
+    }
+
+    /// 所有词语，按添加顺序排列
+    pub fn phrases(&self) -> &[UserPhrase] {
+        &self.phrases
+    }
+
+    /// 连续输入的拼音（如 "nuosu"）完整匹配的词语
+    ///
+    /// 输入可以带音节分隔符（如 "nuo'su"），此时只返回分隔符位于音节之间的词语。
+    pub fn lookup(&self, input: &str) -> Vec<&UserPhrase> {
+        self.index
+            .get(&strip_delimiters(input))
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.phrases[i])
+            .filter(|phrase| respects_delimiters(input, &phrase.pinyin))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.phrases.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.phrases.is_empty()
+    }
+
+    /// 导入 JSON 格式的词库（见 [`UserDictionary::export_json`]）
+    pub fn import_json(&mut self, json_content: &str, mode: LoadMode) -> Result<LoadReport, DictError> {
+        let file: UserDictFile = serde_json::from_str(json_content)?;
+        if file.version != USER_DICT_VERSION {
+            return Err(DictError::UnknownSchemaVersion(file.version.to_string()));
+        }
+
+        let mut report = LoadReport::default();
+        for phrase in file.phrases {
+            let gloss = phrase.gloss.as_deref();
+            match UserPhrase::new(&phrase.pinyin.join(" "), &phrase.text, gloss) {
+                Ok(phrase) => {
+                    self.add(phrase);
+                    report.loaded += 1;
+                }
+                Err(e) => report.check(mode, e)?,
+            }
+        }
+        Ok(report)
+    }
+
+    /// 导入制表符分隔的词库：每行「拼音<TAB>彝文[<TAB>释义]」
+    ///
+    /// 空行和以 # 开头的行被忽略，开头的 UTF-8 BOM 被去掉，
+    /// 第一个有内容的行为 pinyin 开头的表头时跳过。
+    pub fn import_tsv(&mut self, content: &str, mode: LoadMode) -> Result<LoadReport, DictError> {
+        self.import_rows(content, mode, |line| line.split('\t').map(str::to_string).collect())
+    }
+
+    /// 导入逗号分隔的词库，列与 TSV 相同，字段可以用双引号包围
+    pub fn import_csv(&mut self, content: &str, mode: LoadMode) -> Result<LoadReport, DictError> {
+        self.import_rows(content, mode, split_csv_line)
+    }
+
+    /// 导出为 JSON
+    pub fn export_json(&self) -> String {
+        let file = UserDictFile {
+            version: USER_DICT_VERSION,
+            phrases: self.phrases.clone(),
+        };
+        serde_json::to_string_pretty(&file).unwrap_or_default()
+    }
+
+    /// 导出为 TSV，拼音音节之间用空格分隔
+    pub fn export_tsv(&self) -> String {
+        let mut output = String::from("pinyin\ttext\tgloss\n");
+        for phrase in &self.phrases {
+            let gloss = phrase.gloss.as_deref().unwrap_or("");
+            output.push_str(&format!("{}\t{}\t{}\n", phrase.pinyin.join(" "), phrase.text, gloss));
+        }
+        output
+    }
+
+    fn import_rows(
+        &mut self,
+        content: &str,
+        mode: LoadMode,
+        split: impl Fn(&str) -> Vec<String>,
+    ) -> Result<LoadReport, DictError> {
+        let mut report = LoadReport::default();
+        // Excel 等程序保存的文件常以 BOM 开头
+        let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let mut first_row = true;
+        for (number, line) in content.lines().enumerate() {
+            let line = line.trim_end_matches('\r');
+            if line.trim().is_empty() || line.trim_start().starts_with('#') {
+                continue;
+            }
+            let fields = split(line);
+            let is_header = first_row && fields.first().is_some_and(|f| f.trim().eq_ignore_ascii_case("pinyin"));
+            first_row = false;
+            if is_header {
+                continue;
+            }
+
+            let phrase = match fields.as_slice() {
+                [pinyin, text] => UserPhrase::new(pinyin, text, None),
+                [pinyin, text, gloss] => UserPhrase::new(pinyin, text, Some(gloss)),
+                _ => Err(DictError::InvalidEntry {
+                    key: format!("第 {} 行", number + 1),
+                    message: format!("应为 2 或 3 列，实际为 {} 列", fields.len()),
+                }),
+            };
+            match phrase {
+                Ok(phrase) => {
+                    self.add(phrase);
+                    report.loaded += 1;
+                }
+                Err(e) => report.check(mode, e)?,
+            }
+        }
+        Ok(report)
+    }
+
+    fn rebuild_index(&mut self) {
+        self.index.clear();
+        for (i, phrase) in self.phrases.iter().enumerate() {
+            self.index.entry(phrase.input()).or_default().push(i);
+        }
+    }
+}
+
+/// 拆分一行 CSV：支持双引号包围的字段和 "" 转义
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+impl YiIME {
+    /// 与输入完整匹配的用户词语候选项
+    pub fn user_phrase_candidates(&self, input: &str) -> Vec<Candidate> {
+        self.user_dictionary
+            .lookup(input)
+            .into_iter()
+            .map(|phrase| {
+                let kind = if phrase.pinyin.len() > 1 {
+                    CandidateKind::Phrase
+                } else {
+                    CandidateKind::Syllable
+                };
+                Candidate::new(
+                    phrase.text.clone(),
+                    phrase.pinyin.clone(),
+                    kind,
+                    1.0,
+                    CandidateSource::UserDictionary,
+                )
+                .with_gloss(phrase.gloss.clone())
+            })
+            .collect()
+    }
+
+    /// 用户词语的分词方案排在最前面，不在分词结果中时补上
+    pub(crate) fn prefer_user_phrases(&self, input: &str, results: &mut Vec<SegmentResult>) {
+        for phrase in self.user_dictionary.lookup(input).into_iter().rev() {
+            match results.iter().position(|r| r.segments == phrase.pinyin && r.iterations.is_empty()) {
+                Some(position) => {
+                    let result = results.remove(position);
+                    results.insert(0, result);
+                }
+                None => results.insert(0, SegmentResult {
+                    segments: phrase.pinyin.clone(),
+                    yi_chars: phrase.pinyin.iter().map(|s| self.segment_chars(s)).collect(),
+                    confidence: 1.0,
+                    corrected: false,
+                    edit_cost: 0.0,
+                    words: Vec::new(),
+                    iterations: Vec::new(),
+                }),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_after_bom_and_comments_is_skipped() {
+        let mut dictionary = UserDictionary::new();
+        let content = "\u{feff}# 我的词库\r\n\r\npinyin\ttext\tgloss\r\nnuo su\tꆈꌠ\t彝族\r\n";
+        let report = dictionary.import_tsv(content, LoadMode::Strict).unwrap();
+        assert_eq!(report.loaded, 1);
+        assert_eq!(dictionary.lookup("nuosu")[0].gloss.as_deref(), Some("彝族"));
+
+        let mut dictionary = UserDictionary::new();
+        let content = "\u{feff}\"pinyin\",\"text\"\nnuo su,ꆈꌠ\n";
+        assert_eq!(dictionary.import_csv(content, LoadMode::Strict).unwrap().loaded, 1);
+    }
+
+    #[test]
+    fn header_only_on_first_row() {
+        let mut dictionary = UserDictionary::new();
+        let content = "nuo su\tꆈꌠ\npinyin\ttext\n";
+        assert!(dictionary.import_tsv(content, LoadMode::Strict).is_err());
+        let report = dictionary.import_tsv(content, LoadMode::Lenient).unwrap();
+        assert_eq!(report.loaded, 1);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn delimiters_must_match_phrase_syllables() {
+        let mut dictionary = UserDictionary::new();
+        dictionary.add(UserPhrase::new("nuo su", "ꆈꌠ", None).unwrap());
+        assert_eq!(dictionary.lookup("nuo'su").len(), 1);
+        assert!(dictionary.lookup("nu'osu").is_empty());
+    }
+}
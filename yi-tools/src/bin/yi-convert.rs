@@ -0,0 +1,123 @@
+//! 将拼音文稿批量转写为彝文
+//!
+//! 用法：yi-convert [选项] [文件...]
+//! 没有指定文件时从标准输入读取，结果写到标准输出。
+
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use yi_core::{join_tokens, TextToken, YiIME};
+use yi_tools::EngineFiles;
+
+const USAGE: &str = "用法: yi-convert [选项] [文件...]
+
+将彝文拼音文本转写为彝文。没有指定文件时从标准输入读取。
+
+选项:
+  -o, --output <文件>      写入文件而不是标准输出
+      --dict <文件>        使用指定的音节字典代替内置字典
+      --radicals <文件>    使用指定的部首字典代替内置字典
+      --lexicon <文件>     使用指定的词库代替内置词库
+  -q, --quiet              不报告无法转写的词和词库中无法加载的词
+  -h, --help               显示本帮助";
+
+struct Options {
+    inputs: Vec<String>,
+    output: Option<String>,
+    files: EngineFiles,
+    quiet: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        inputs: Vec::new(),
+        output: None,
+        files: EngineFiles::default(),
+        quiet: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} 需要一个参数", name));
+        match arg.as_str() {
+            "-o" | "--output" => options.output = Some(value(&arg)?),
+            "--dict" => options.files.dict = Some(value(&arg)?),
+            "--radicals" => options.files.radicals = Some(value(&arg)?),
+            "--lexicon" => options.files.lexicon = Some(value(&arg)?),
+            "-q" | "--quiet" => options.quiet = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') && arg != "-" => return Err(format!("未知选项: {}", arg)),
+            _ => options.inputs.push(arg),
+        }
+    }
+    Ok(options)
+}
+
+fn load_engine(options: &Options) -> Result<YiIME, String> {
+    let (ime, report) = yi_tools::load_engine(&options.files)?;
+    if !options.quiet {
+        for warning in &report.warnings {
+            eprintln!("词库: {}", warning);
+        }
+    }
+    Ok(ime)
+}
+
+fn read_input(path: &str) -> Result<String, String> {
+    if path == "-" {
+        let mut text = String::new();
+        io::stdin()
+            .read_to_string(&mut text)
+            .map_err(|e| format!("读取标准输入失败: {}", e))?;
+        Ok(text)
+    } else {
+        fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path, e))
+    }
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let ime = load_engine(&options)?;
+    let inputs = if options.inputs.is_empty() {
+        vec!["-".to_string()]
+    } else {
+        options.inputs.clone()
+    };
+
+    let mut output = String::new();
+    for path in &inputs {
+        let text = read_input(path)?;
+        let tokens = ime.transliterate_tokens(&text);
+        if !options.quiet {
+            // 全小写却无法转写的词很可能是拼写错误
+            for token in &tokens {
+                if let TextToken::Other(word) = token {
+                    if word.chars().all(|c| c.is_ascii_lowercase()) {
+                        eprintln!("{}: 无法转写 {}", path, word);
+                    }
+                }
+            }
+        }
+        output.push_str(&join_tokens(&tokens));
+    }
+
+    match &options.output {
+        Some(path) => fs::write(path, output).map_err(|e| format!("写入 {} 失败: {}", path, e)),
+        None => io::stdout()
+            .write_all(output.as_bytes())
+            .map_err(|e| format!("写入标准输出失败: {}", e)),
+    }
+}
+
+fn main() -> ExitCode {
+    let result = parse_args().and_then(run);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("yi-convert: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
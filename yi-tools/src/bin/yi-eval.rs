@@ -0,0 +1,393 @@
+//! 用彝文语料评估候选项排序
+//!
+//! 用法：yi-eval [选项] <文件...>
+//! 把语料中的每个词转写为拼音，通过 Composer 逐键输入并选择该词，
+//! 统计平均按键数和候选项排序的正确率；可以同时比较两种引擎配置。
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fs;
+use std::process::ExitCode;
+use std::sync::Arc;
+use yi_core::{
+    is_yi_char, Action, BigramModel, Composer, CorrectionOptions, Key, Modifiers, RomanToken, YiIME,
+    ITERATION_MARK, MAX_CANDIDATES,
+};
+use yi_tools::EngineFiles;
+
+const USAGE: &str = "用法: yi-eval [选项] <文件...>
+
+把彝文语料中的每个词（连续的彝文字符）转写为拼音，逐键输入并选择该词，
+统计平均每字按键数、首选/前三/前九正确率和排序最差的词。
+
+选项:
+  -c, --config <配置>      引擎配置，最多指定两次以并排比较；没有指定时使用默认配置
+  -n, --worst <数量>       列出排序最差的词的数量（默认 10）
+  -h, --help               显示本帮助
+
+配置为逗号分隔的设置，空字符串表示默认配置:
+  dict=<文件>              音节字典
+  radicals=<文件>          部首字典
+  lexicon=<文件>           词库（代替内置词库）
+  nolexicon                不加载词库
+  model=<文件>             二元语言模型（yi-train 输出的 bigram.txt）
+  toneless                 无声调输入
+  correction               拼写纠错
+  learn                    记住每次选择（默认每个词都从空的输入历史开始）
+
+例: yi-eval 语料.txt -c \"\" -c model=模型/bigram.txt";
+
+struct Options {
+    inputs: Vec<String>,
+    configs: Vec<String>,
+    worst: usize,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        inputs: Vec::new(),
+        configs: Vec::new(),
+        worst: 10,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} 需要一个参数", name));
+        match arg.as_str() {
+            "-c" | "--config" => options.configs.push(value(&arg)?),
+            "-n" | "--worst" => {
+                let worst = value(&arg)?;
+                options.worst = worst.parse().map_err(|_| format!("{} 不是有效的数量", worst))?;
+            }
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => return Err(format!("未知选项: {}", arg)),
+            _ => options.inputs.push(arg),
+        }
+    }
+    if options.inputs.is_empty() {
+        return Err(format!("没有指定语料\n\n{}", USAGE));
+    }
+    if options.configs.len() > 2 {
+        return Err("最多比较两种配置".to_string());
+    }
+    if options.configs.is_empty() {
+        options.configs.push(String::new());
+    }
+    Ok(options)
+}
+
+/// 一种引擎配置
+struct Config {
+    name: String,
+    engine: Arc<YiIME>,
+    learn: bool,
+}
+
+impl Config {
+    fn parse(spec: &str) -> Result<Self, String> {
+        let mut files = EngineFiles::default();
+        let mut model = None;
+        let mut toneless = false;
+        let mut correction = false;
+        let mut learn = false;
+        for setting in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let read = |path: &str| fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path, e));
+            match setting.split_once('=') {
+                Some(("dict", path)) => files.dict = Some(path.to_string()),
+                Some(("radicals", path)) => files.radicals = Some(path.to_string()),
+                Some(("lexicon", path)) => files.lexicon = Some(path.to_string()),
+                Some(("model", path)) => model = Some(read(path)?),
+                None if setting == "nolexicon" => files.no_lexicon = true,
+                None if setting == "toneless" => toneless = true,
+                None if setting == "correction" => correction = true,
+                None if setting == "learn" => learn = true,
+                _ => return Err(format!("未知的配置项: {}", setting)),
+            }
+        }
+
+        let (mut ime, _) = yi_tools::load_engine(&files)?;
+        if let Some(model) = model {
+            ime.set_language_model(BigramModel::parse(&model).map_err(|e| format!("加载语言模型失败: {}", e))?);
+        }
+        ime.options.toneless = toneless;
+        if correction {
+            ime.options.correction = Some(CorrectionOptions::default());
+        }
+
+        let name = if spec.trim().is_empty() { "默认".to_string() } else { spec.to_string() };
+        Ok(Self { name, engine: Arc::new(ime), learn })
+    }
+}
+
+/// 语料中的一个词及其输入
+struct Word {
+    text: String,
+    /// 依次输入的按键，ꀕ 输入为 w
+    input: String,
+    /// 每个字单独输入时的 (字, 按键)
+    chars: Vec<(String, String)>,
+}
+
+/// 把语料切分为词；含有字典中没有的字符的词被跳过
+fn read_words(engine: &YiIME, inputs: &[String]) -> Result<(Vec<Word>, usize), String> {
+    let mut words = Vec::new();
+    let mut skipped = 0;
+    for path in inputs {
+        let text = fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path, e))?;
+        for run in text.split(|c: char| !is_yi_char(c)).filter(|run| !run.is_empty()) {
+            let romanization = engine.romanize(run);
+            if !romanization.is_complete() {
+                skipped += 1;
+                continue;
+            }
+            let mut input = String::new();
+            let mut chars = Vec::new();
+            for token in romanization.tokens {
+                if let RomanToken::Yi { text, pinyin } = token {
+                    let keys = if text == ITERATION_MARK { engine.options.iteration_key.to_string() } else { pinyin };
+                    input.push_str(&keys);
+                    chars.push((text.to_string(), keys));
+                }
+            }
+            words.push(Word { text: run.to_string(), input, chars });
+        }
+    }
+    Ok((words, skipped))
+}
+
+/// 一种配置的评估结果
+#[derive(Default)]
+struct Report {
+    words: usize,
+    chars: usize,
+    keystrokes: usize,
+    /// 整词输入无法得到、逐字输入也无法得到的词数
+    unreachable: usize,
+    top1: usize,
+    top3: usize,
+    top9: usize,
+    /// 词 -> 排名
+    ranks: HashMap<String, WordRank>,
+}
+
+/// 一个词的排名
+struct WordRank {
+    /// 最差的排名，None 表示不在候选中
+    rank: Option<usize>,
+    /// 出现次数
+    count: usize,
+    input: String,
+}
+
+impl Report {
+    fn percent(&self, count: usize) -> String {
+        if self.words == 0 {
+            return "-".to_string();
+        }
+        format!("{:.1}%", count as f64 * 100.0 / self.words as f64)
+    }
+
+    fn keystrokes_per_char(&self) -> String {
+        if self.chars == 0 {
+            return "-".to_string();
+        }
+        format!("{:.3}", self.keystrokes as f64 / self.chars as f64)
+    }
+
+    /// 排序最差的词：不在候选中的最差，其次按排名、出现次数和文本排列
+    fn worst(&self, limit: usize) -> Vec<(&String, &WordRank)> {
+        let mut ranks: Vec<_> = self.ranks.iter().filter(|(_, word)| word.rank != Some(0)).collect();
+        ranks.sort_by_key(|(text, word)| (word.rank.map_or(0, |rank| usize::MAX - rank), Reverse(word.count), *text));
+        ranks.truncate(limit);
+        ranks
+    }
+}
+
+/// 通过 Composer 输入和选择
+struct Typist {
+    composer: Composer,
+    learn: bool,
+}
+
+impl Typist {
+    /// 输入 keys，返回 text 在候选项中的位置；输入结束后清空缓冲区
+    fn rank(&mut self, keys: &str, text: &str) -> Option<usize> {
+        for ch in keys.chars() {
+            self.composer.handle_key(Key::Char(ch), Modifiers::default());
+        }
+        let rank = self
+            .composer
+            .candidates()
+            .iter()
+            .take(MAX_CANDIDATES)
+            .position(|candidate| candidate.text == text);
+        match rank {
+            // 选择该候选项（让输入历史记住这次选择）
+            Some(rank) => {
+                let key = if rank == 0 { Key::Space } else { Key::Select(rank + 1) };
+                let actions = self.composer.handle_key(key, Modifiers::default());
+                debug_assert!(actions.iter().any(|action| matches!(action, Action::Commit(_))));
+                if !self.learn {
+                    self.composer.history_mut().reset();
+                }
+            }
+            None => {
+                self.composer.handle_key(Key::Escape, Modifiers::default());
+            }
+        }
+        rank
+    }
+
+    /// 输入一个词，返回整词输入时的排名和所用的按键数（逐字输入也无法得到时为 None）
+    fn type_word(&mut self, word: &Word) -> (Option<usize>, Option<usize>) {
+        let rank = self.rank(&word.input, &word.text);
+        if rank.is_some() {
+            // 输入拼音后按空格或数字键选择
+            return (rank, Some(word.input.len() + 1));
+        }
+
+        // 整词不在候选中：输入的拼音作废，再逐字输入
+        let mut keystrokes = word.input.len() + 1;
+        for (text, keys) in &word.chars {
+            if self.rank(keys, text).is_none() {
+                return (None, None);
+            }
+            keystrokes += keys.len() + 1;
+        }
+        (None, Some(keystrokes))
+    }
+}
+
+fn evaluate(config: &Config, words: &[Word]) -> Report {
+    let mut typist = Typist {
+        composer: Composer::new(config.engine.clone()),
+        learn: config.learn,
+    };
+    let mut report = Report::default();
+    for word in words {
+        let (rank, keystrokes) = typist.type_word(word);
+        report.words += 1;
+        match keystrokes {
+            Some(keystrokes) => {
+                report.keystrokes += keystrokes;
+                report.chars += word.chars.len();
+            }
+            None => report.unreachable += 1,
+        }
+        report.top1 += usize::from(rank.is_some_and(|rank| rank < 1));
+        report.top3 += usize::from(rank.is_some_and(|rank| rank < 3));
+        report.top9 += usize::from(rank.is_some());
+
+        let entry = report.ranks.entry(word.text.clone()).or_insert(WordRank {
+            rank,
+            count: 0,
+            input: word.input.clone(),
+        });
+        // 开启学习时同一个词的排名可能变化，保留最差的
+        let worse = match (rank, entry.rank) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(rank), Some(worst)) => rank > worst,
+        };
+        if worse {
+            entry.rank = rank;
+        }
+        entry.count += 1;
+    }
+    report
+}
+
+/// 终端中的显示宽度：中日韩文字、彝文和全角符号占两列
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60 | 0xFFE0..=0xFFE6 | 0x20000..=0x3FFFD => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+/// 在左侧补空格，使显示宽度至少为 width
+fn pad_left(text: &str, width: usize) -> String {
+    format!("{}{}", " ".repeat(width.saturating_sub(display_width(text))), text)
+}
+
+/// 在右侧补空格，使显示宽度至少为 width
+fn pad_right(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(display_width(text))))
+}
+
+fn print_reports(configs: &[Config], reports: &[Report], skipped: usize, worst: usize) {
+    let row = |value: fn(&Report) -> String| reports.iter().map(value).collect::<Vec<_>>();
+    let rows = [
+        ("词数", row(|r| r.words.to_string())),
+        ("每字按键数", row(|r| r.keystrokes_per_char())),
+        ("首选正确率", row(|r| r.percent(r.top1))),
+        ("前三正确率", row(|r| r.percent(r.top3))),
+        ("前九正确率", row(|r| r.percent(r.top9))),
+        ("无法输入", row(|r| r.unreachable.to_string())),
+        ("不同的词", row(|r| r.ranks.len().to_string())),
+    ];
+
+    let width = configs.iter().map(|c| display_width(&c.name)).max().unwrap_or(0).max(10);
+    let mut header = pad_right("", 12);
+    for config in configs {
+        header.push_str(&format!("  {}", pad_left(&config.name, width)));
+    }
+    println!("{}", header.trim_end());
+    for (label, values) in &rows {
+        let mut line = pad_right(label, 12);
+        for value in values {
+            line.push_str(&format!("  {}", pad_left(value, width)));
+        }
+        println!("{}", line);
+    }
+    if skipped > 0 {
+        println!("跳过 {} 个含有字典中没有的字符的词", skipped);
+    }
+
+    for (config, report) in configs.iter().zip(reports) {
+        let worst_words = report.worst(worst);
+        if worst_words.is_empty() {
+            continue;
+        }
+        println!();
+        println!("排序最差的词（{}）:", config.name);
+        for (text, word) in worst_words {
+            let rank = match word.rank {
+                Some(rank) => format!("第 {} 位", rank + 1),
+                None => "不在候选中".to_string(),
+            };
+            println!("  {} ({})  {}  出现 {} 次", text, word.input, rank, word.count);
+        }
+    }
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let configs = options
+        .configs
+        .iter()
+        .map(|spec| Config::parse(spec))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 按第一种配置的字典切分语料，两种配置评估相同的词
+    let (words, skipped) = read_words(&configs[0].engine, &options.inputs)?;
+    let reports: Vec<Report> = configs.iter().map(|config| evaluate(config, &words)).collect();
+    print_reports(&configs, &reports, skipped, options.worst);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args().and_then(run);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("yi-eval: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! 由语料统计音节频率和二元计数
+//!
+//! 用法：yi-train [选项] <文件或目录...>
+//! 彝文语料按字典转写为拼音，拼音语料先转写为彝文再统计；
+//! 结果写为输入法可以直接加载的 bigram.txt 和 frequency.json。
+
+use serde_json::{json, Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use yi_core::{is_yi_char, BigramModel, RomanToken, YiIME, DICTIONARY_SCHEMA_VERSION, ITERATION_MARK, SENTENCE_START};
+use yi_tools::EngineFiles;
+
+const USAGE: &str = "用法: yi-train [选项] <文件或目录...>
+
+统计语料中的字频、词频和音节二元计数。目录中的 .txt 文件按路径顺序递归读取。
+含有彝文字符的行按字典转写为拼音，其余行视为拼音，先转写为彝文。
+
+输出（相同的语料总是得到相同的文件）:
+  bigram.txt       音节一元和二元计数，可用 BigramModel::load 加载
+  frequency.json   带频率的音节字典（格式版本 3），词频取自词库中的词
+
+选项:
+  -o, --output <目录>      输出目录（默认为当前目录）
+      --dict <文件>        使用指定的音节字典代替内置字典
+      --radicals <文件>    使用指定的部首字典代替内置字典
+      --lexicon <文件>     使用指定的词库代替内置词库
+  -q, --quiet              不输出统计摘要
+  -h, --help               显示本帮助";
+
+struct Options {
+    inputs: Vec<String>,
+    output: PathBuf,
+    files: EngineFiles,
+    quiet: bool,
+}
+
+fn parse_args() -> Result<Options, String> {
+    let mut options = Options {
+        inputs: Vec::new(),
+        output: PathBuf::from("."),
+        files: EngineFiles::default(),
+        quiet: false,
+    };
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or_else(|| format!("{} 需要一个参数", name));
+        match arg.as_str() {
+            "-o" | "--output" => options.output = PathBuf::from(value(&arg)?),
+            "--dict" => options.files.dict = Some(value(&arg)?),
+            "--radicals" => options.files.radicals = Some(value(&arg)?),
+            "--lexicon" => options.files.lexicon = Some(value(&arg)?),
+            "-q" | "--quiet" => options.quiet = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                std::process::exit(0);
+            }
+            _ if arg.starts_with('-') => return Err(format!("未知选项: {}", arg)),
+            _ => options.inputs.push(arg),
+        }
+    }
+    if options.inputs.is_empty() {
+        return Err(format!("没有指定语料\n\n{}", USAGE));
+    }
+    Ok(options)
+}
+
+fn load_engine(options: &Options) -> Result<YiIME, String> {
+    let (ime, report) = yi_tools::load_engine(&options.files)?;
+    if !options.quiet {
+        for warning in &report.warnings {
+            eprintln!("词库: {}", warning);
+        }
+    }
+    Ok(ime)
+}
+
+/// 展开目录，得到按路径排序的语料文件
+fn collect_files(inputs: &[String]) -> Result<Vec<PathBuf>, String> {
+    let mut files = Vec::new();
+    for input in inputs {
+        let path = PathBuf::from(input);
+        if path.is_dir() {
+            walk(&path, &mut files)?;
+        } else {
+            files.push(path);
+        }
+    }
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("读取目录 {} 失败: {}", dir.display(), e))?;
+    for entry in entries {
+        let path = entry.map_err(|e| format!("读取目录 {} 失败: {}", dir.display(), e))?.path();
+        if path.is_dir() {
+            walk(&path, files)?;
+        } else if path.extension().is_some_and(|extension| extension == "txt") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// 语料统计
+#[derive(Default)]
+struct Counts {
+    model: BigramModel,
+    /// 彝文字符 -> 出现次数
+    chars: HashMap<String, u64>,
+    /// 词库中的词 -> 出现次数
+    phrases: HashMap<String, u64>,
+    lines: usize,
+    syllables: u64,
+    /// 字典中没有的彝文字符 -> 出现次数
+    unknown: BTreeMap<char, u64>,
+}
+
+impl Counts {
+    /// 统计一行语料：标点和其他非彝文文本把一行分成若干句，空白不分句
+    fn add_line(&mut self, ime: &YiIME, words: &HashMap<char, Vec<String>>, line: &str) {
+        let text = if line.chars().any(is_yi_char) {
+            line.to_string()
+        } else {
+            ime.transliterate_text(line)
+        };
+        self.lines += 1;
+
+        let mut previous: Option<String> = None;
+        let mut sentence = String::new();
+        for token in ime.romanize(&text).tokens {
+            match token {
+                RomanToken::Yi { text, pinyin } => {
+                    self.model.add_unigram(&pinyin, 1);
+                    self.model
+                        .add_bigram(previous.as_deref().unwrap_or(SENTENCE_START), &pinyin, 1);
+                    self.syllables += 1;
+                    if text != ITERATION_MARK {
+                        *self.chars.entry(text.to_string()).or_default() += 1;
+                    }
+                    sentence.push(text);
+                    previous = Some(pinyin);
+                }
+                RomanToken::Other(other) if other.chars().all(char::is_whitespace) => {}
+                other => {
+                    if let RomanToken::Unknown(ch) = other {
+                        *self.unknown.entry(ch).or_default() += 1;
+                    }
+                    self.add_phrases(words, &sentence);
+                    sentence.clear();
+                    previous = None;
+                }
+            }
+        }
+        self.add_phrases(words, &sentence);
+    }
+
+    /// 统计一句中出现的词库中的词（可以重叠）
+    fn add_phrases(&mut self, words: &HashMap<char, Vec<String>>, sentence: &str) {
+        for (start, ch) in sentence.char_indices() {
+            for word in words.get(&ch).into_iter().flatten() {
+                if sentence[start..].starts_with(word.as_str()) {
+                    *self.phrases.entry(word.clone()).or_default() += 1;
+                }
+            }
+        }
+    }
+}
+
+/// 带频率的音节字典：保留原有的读音和元数据，键按码位排序
+fn frequency_dictionary(ime: &YiIME, counts: &Counts) -> String {
+    let sorted: BTreeMap<&String, &Vec<String>> = ime.dictionary.iter().collect();
+    let mut entries = Map::new();
+    for (yi_char, readings) in sorted {
+        let mut entry = Map::new();
+        entry.insert("readings".to_string(), json!(readings));
+        entry.insert("frequency".to_string(), json!(counts.chars.get(yi_char).copied().unwrap_or(0)));
+        if let Some(metadata) = ime.entry(yi_char) {
+            if !metadata.standard {
+                entry.insert("standard".to_string(), json!(false));
+            }
+            if let Some(gloss) = &metadata.gloss {
+                entry.insert("gloss".to_string(), json!(gloss));
+            }
+            if let Some(name) = &metadata.unicode_name {
+                entry.insert("unicode_name".to_string(), json!(name));
+            }
+        }
+        entries.insert(yi_char.clone(), Value::Object(entry));
+    }
+
+    let sorted: BTreeMap<&String, &u64> = counts.phrases.iter().collect();
+    let phrases: Map<String, Value> = sorted
+        .into_iter()
+        .map(|(phrase, count)| (phrase.clone(), json!(count)))
+        .collect();
+
+    let dictionary = json!({
+        "version": DICTIONARY_SCHEMA_VERSION,
+        "entries": entries,
+        "phrases": phrases,
+    });
+    let mut output = serde_json::to_string_pretty(&dictionary).unwrap_or_default();
+    output.push('\n');
+    output
+}
+
+fn run(options: Options) -> Result<(), String> {
+    let ime = load_engine(&options)?;
+    let files = collect_files(&options.inputs)?;
+
+    let mut words: HashMap<char, Vec<String>> = HashMap::new();
+    for word in ime.lexicon.words() {
+        if let Some(first) = word.text.chars().next() {
+            let texts = words.entry(first).or_default();
+            if !texts.contains(&word.text) {
+                texts.push(word.text.clone());
+            }
+        }
+    }
+
+    let mut counts = Counts::default();
+    for file in &files {
+        let text = fs::read_to_string(file).map_err(|e| format!("读取 {} 失败: {}", file.display(), e))?;
+        for line in text.lines() {
+            counts.add_line(&ime, &words, line);
+        }
+    }
+
+    fs::create_dir_all(&options.output)
+        .map_err(|e| format!("创建目录 {} 失败: {}", options.output.display(), e))?;
+    let write = |name: &str, content: String| {
+        let path = options.output.join(name);
+        fs::write(&path, content).map_err(|e| format!("写入 {} 失败: {}", path.display(), e))
+    };
+    write("bigram.txt", counts.model.export())?;
+    write("frequency.json", frequency_dictionary(&ime, &counts))?;
+
+    if !options.quiet {
+        eprintln!(
+            "{} 个文件，{} 行，{} 个音节，{} 种音节，{} 个词",
+            files.len(),
+            counts.lines,
+            counts.syllables,
+            counts.model.vocabulary_size(),
+            counts.phrases.values().sum::<u64>()
+        );
+        for (ch, count) in &counts.unknown {
+            eprintln!("字典中没有 {} (U+{:04X})，出现 {} 次", ch, *ch as u32, count);
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let result = parse_args().and_then(run);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("yi-train: {}", message);
+            ExitCode::FAILURE
+        }
+    }
+}
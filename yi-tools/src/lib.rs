@@ -0,0 +1,46 @@
+//! 命令行工具共用的代码
+
+use std::fs;
+use yi_core::{LoadMode, LoadReport, YiIME};
+
+/// 加载引擎时使用的数据文件，None 表示使用内置数据
+#[derive(Debug, Clone, Default)]
+pub struct EngineFiles {
+    /// 音节字典
+    pub dict: Option<String>,
+    /// 部首字典
+    pub radicals: Option<String>,
+    /// 词库
+    pub lexicon: Option<String>,
+    /// 不加载词库
+    pub no_lexicon: bool,
+}
+
+/// 加载音节字典、部首字典和词库，返回引擎和词库的加载报告
+///
+/// 字典按严格模式加载；内置词库中的词不一定都能用指定的字典读出，因此词库宽松加载。
+pub fn load_engine(files: &EngineFiles) -> Result<(YiIME, LoadReport), String> {
+    let mut ime = YiIME::new();
+    match &files.dict {
+        Some(path) => ime.load_dictionary(path),
+        None => ime.load_dictionary_from_str(yi_core::YI_SYLLABLE_DICT),
+    }
+    .map_err(|e| format!("加载音节字典失败: {}", e))?;
+    match &files.radicals {
+        Some(path) => ime.load_radical_dictionary(path),
+        None => ime.load_radical_dictionary_from_str(yi_core::YI_RADICAL_DICT),
+    }
+    .map_err(|e| format!("加载部首字典失败: {}", e))?;
+
+    if files.no_lexicon {
+        return Ok((ime, LoadReport::default()));
+    }
+    let lexicon = match &files.lexicon {
+        Some(path) => fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path, e))?,
+        None => yi_core::YI_LEXICON.to_string(),
+    };
+    let report = ime
+        .load_lexicon_with_mode(&lexicon, LoadMode::Lenient)
+        .map_err(|e| format!("加载词库失败: {}", e))?;
+    Ok((ime, report))
+}